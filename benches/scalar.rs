@@ -55,6 +55,43 @@ pub fn bench_scalar(c: &mut Criterion) {
         }
     );
 
+    c.bench_function(
+        "invert",
+        |b| {
+            b.iter_batched(
+                Scalar::rand,
+                |x| {
+                    black_box(x.invert());
+                },
+                BatchSize::SmallInput
+            )
+        }
+    );
+
+    c.bench_function(
+        "sqrt",
+        |b| {
+            b.iter_batched(
+                || Scalar::rand().square(),
+                |x| {
+                    black_box(x.sqrt());
+                },
+                BatchSize::SmallInput
+            )
+        }
+    );
+
+    c.bench_function(
+        "batch_invert-large",
+        |b| {
+            b.iter_batched(
+                || ((0..256).map(|_| Scalar::rand()).collect::<Vec<_>>(), vec![Scalar::ZERO; 256]),
+                |(x, mut out)| Scalar::batch_invert(&x, &mut out),
+                BatchSize::LargeInput,
+            )
+        },
+    );
+
     c.bench_function(
         "batch_multiplicative_inverse-tiny",
         |b| {
@@ -0,0 +1,117 @@
+use super::curve::Point;
+use super::nonce::derive_nonce;
+use super::scalar_field::Scalar;
+use super::schnorr::{schnorr_challenge, SchnorrSignature};
+use super::GFp;
+
+// Adaptor signatures for the Schnorr signatures of `schnorr`, the building block of
+// scriptless scripts such as atomic swaps.
+//
+// A pre-signature for the adaptor point T = t * G commits to the nonce point R + T:
+//   R = k * G, e = hash(R + T, pk, m), s' = k - e * sk
+// It is checked by recomputing R = s' * G + e * pk and the challenge from R + T. Anyone who
+// knows t can adapt it into the Schnorr signature (s' + t, e), and anyone who sees both the
+// pre-signature and the signature learns t = s - s'.
+
+const ADAPTOR_NONCE_DOMAIN: &[u8] = b"ecgfp5-adaptor-nonce";
+
+/// A pre-signature. It has the shape of a Schnorr signature, but doesn't verify as one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AdaptorPreSignature {
+    pub s: Scalar,
+    pub e: Scalar,
+}
+
+/// Pre-signs `message` with `sk` for the adaptor point `t`. The nonce is derived deterministically
+/// with `derive_nonce`, from the adaptor point and the message, in a domain of its own so that it
+/// never matches the nonce of a plain Schnorr signature.
+pub fn adaptor_pre_sign(sk: Scalar, t: Point, message: &[GFp]) -> AdaptorPreSignature {
+    let pk = Point::GENERATOR * sk;
+
+    let mut nonce_inputs = t.encode().0.to_vec();
+    nonce_inputs.extend_from_slice(message);
+    let k = derive_nonce(ADAPTOR_NONCE_DOMAIN, sk, &nonce_inputs, None);
+
+    let e = schnorr_challenge(Point::GENERATOR * k + t, pk, message);
+    AdaptorPreSignature { s: k - e * sk, e }
+}
+
+/// Checks that `pre_signature` is a pre-signature of `message` under `pk` for the adaptor point `t`,
+/// i.e. that it adapts into a valid signature with the discrete logarithm of `t`.
+pub fn adaptor_pre_verify(pk: Point, t: Point, message: &[GFp], pre_signature: &AdaptorPreSignature) -> bool {
+    let r = Point::GENERATOR * pre_signature.s + pk * pre_signature.e;
+    schnorr_challenge(r + t, pk, message) == pre_signature.e
+}
+
+/// Completes `pre_signature` into a Schnorr signature with the adaptor secret `t`.
+pub fn adaptor_adapt(pre_signature: &AdaptorPreSignature, t: Scalar) -> SchnorrSignature {
+    SchnorrSignature {
+        s: pre_signature.s + t,
+        e: pre_signature.e,
+    }
+}
+
+/// Extracts the adaptor secret from a pre-signature and the signature it was adapted into.
+/// Returns `None` if the signature doesn't come from the pre-signature.
+pub fn adaptor_extract(pre_signature: &AdaptorPreSignature, signature: &SchnorrSignature) -> Option<Scalar> {
+    if pre_signature.e != signature.e {
+        return None;
+    }
+    Some(signature.s - pre_signature.s)
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2_field::types::{Field, Sample};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::curve::schnorr::{schnorr_sign, schnorr_verify};
+
+    #[test]
+    fn test_adaptor_signature() {
+        let mut rng = thread_rng();
+
+        let sk = Scalar::sample(&mut rng);
+        let pk = Point::GENERATOR * sk;
+        let t = Scalar::sample(&mut rng);
+        let t_point = Point::GENERATOR * t;
+        let message = [GFp::sample(&mut rng), GFp::sample(&mut rng)];
+
+        let pre_sig = adaptor_pre_sign(sk, t_point, &message);
+        assert!(adaptor_pre_verify(pk, t_point, &message, &pre_sig));
+        assert!(!adaptor_pre_verify(pk, t_point + Point::GENERATOR, &message, &pre_sig));
+        assert!(!adaptor_pre_verify(pk, t_point, &message[..1], &pre_sig));
+        assert!(!adaptor_pre_verify(Point::sample(&mut rng), t_point, &message, &pre_sig));
+
+        // the pre-signature isn't a signature, until adapted with t
+        let pre_sig_as_sig = SchnorrSignature { s: pre_sig.s, e: pre_sig.e };
+        assert!(!schnorr_verify(pk, &message, &pre_sig_as_sig));
+        let sig = adaptor_adapt(&pre_sig, t);
+        assert!(schnorr_verify(pk, &message, &sig));
+        assert!(!schnorr_verify(pk, &message, &adaptor_adapt(&pre_sig, t + Scalar::ONE)));
+
+        assert_eq!(adaptor_extract(&pre_sig, &sig), Some(t));
+        let other_sig = SchnorrSignature { s: sig.s, e: sig.e + Scalar::ONE };
+        assert_eq!(adaptor_extract(&pre_sig, &other_sig), None);
+    }
+
+    #[test]
+    fn test_adaptor_nonce_differs_from_schnorr() {
+        let mut rng = thread_rng();
+
+        let sk = Scalar::sample(&mut rng);
+        let t_point = Point::sample(&mut rng);
+        let message = [GFp::sample(&mut rng), GFp::sample(&mut rng)];
+
+        // a plain signature on the nonce inputs of the pre-signature must not reuse its nonce,
+        // or s' + e' * sk == s + e * sk would reveal sk
+        let pre_sig = adaptor_pre_sign(sk, t_point, &message);
+        let mut nonce_inputs = t_point.encode().0.to_vec();
+        nonce_inputs.extend_from_slice(&message);
+        let sig = schnorr_sign(sk, &nonce_inputs);
+
+        assert_ne!(pre_sig.s + pre_sig.e * sk, sig.s + sig.e * sk);
+        assert_ne!((pre_sig.s - sig.s) / (sig.e - pre_sig.e), sk);
+    }
+}
@@ -2,10 +2,11 @@ use plonky2::hash::hash_types::RichField;
 use plonky2_field::{
     extension::{quintic::QuinticExtension, Extendable, FieldExtension, Frobenius},
     ops::Square,
-    types::{Field, PrimeField},
+    types::{Field, Field64, PrimeField, PrimeField64, Sample},
 };
+use rand::RngCore;
 
-use super::{GFp, GFp5};
+pub use super::{GFp, GFp5};
 
 pub trait Legendre<F: Field> {
     fn legendre(&self) -> F;
@@ -76,7 +77,7 @@ impl Sgn0 for QuinticExtension<GFp> {
 /// This is used to canonicalize the square root
 /// This is an implementation of the function sgn0 from the IRTF's hash-to-curve document
 /// https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-hash-to-curve-07#name-the-sgn0-function
-pub(crate) fn quintic_ext_sgn0<F: RichField + Extendable<5>>(x: QuinticExtension<F>) -> bool {
+pub fn quintic_ext_sgn0<F: RichField + Extendable<5>>(x: QuinticExtension<F>) -> bool {
     let mut sign = false;
     let mut zero = true;
     for &limb in x.0.iter() {
@@ -90,7 +91,7 @@ pub(crate) fn quintic_ext_sgn0<F: RichField + Extendable<5>>(x: QuinticExtension
 
 // returns the "canoncal" square root of x, if it exists
 // the "canonical" square root is the one such that `sgn0(sqrt(x)) == true`
-pub(crate) fn canonical_sqrt_quintic_ext_goldilocks(x: GFp5) -> Option<GFp5> {
+pub fn canonical_sqrt_quintic_ext_goldilocks(x: GFp5) -> Option<GFp5> {
     match sqrt_quintic_ext_goldilocks(x) {
         Some(root_x) => {
             if quintic_ext_sgn0(root_x) {
@@ -105,7 +106,7 @@ pub(crate) fn canonical_sqrt_quintic_ext_goldilocks(x: GFp5) -> Option<GFp5> {
 
 /// returns `Some(sqrt(x))` if `x` is a square in the field, and `None` otherwise
 /// basically copied from here: https://github.com/pornin/ecquintic_ext/blob/ce059c6d1e1662db437aecbf3db6bb67fe63c716/python/ecGFp5.py#L879
-pub(crate) fn sqrt_quintic_ext_goldilocks(x: GFp5) -> Option<GFp5> {
+pub fn sqrt_quintic_ext_goldilocks(x: GFp5) -> Option<GFp5> {
     let v = x.exp_power_of_2(31);
     let d = x * v.exp_power_of_2(32) * v.try_inverse().unwrap_or(GFp5::ZERO);
     let e = (d * d.repeated_frobenius(2)).frobenius();
@@ -118,6 +119,45 @@ pub(crate) fn sqrt_quintic_ext_goldilocks(x: GFp5) -> Option<GFp5> {
     g.sqrt().map(|s| e.inverse_or_zero() * s.into())
 }
 
+/// Encodes `x` into 40 bytes: each limb, in canonical form, as 8 little-endian bytes, lowest limb first.
+/// This is the same byte order as the `quintic_ext_to_bytes` gadget.
+pub fn gfp5_encode(x: GFp5) -> [u8; 40] {
+    let mut buf = [0u8; 40];
+    for (chunk, limb) in buf.chunks_exact_mut(8).zip(x.0.iter()) {
+        chunk.copy_from_slice(&limb.to_canonical_u64().to_le_bytes());
+    }
+    buf
+}
+
+/// Decodes 40 bytes produced by `gfp5_encode`. Returns `None` if any limb is not in canonical form,
+/// so that every field element has exactly one valid encoding.
+pub fn gfp5_decode(buf: &[u8; 40]) -> Option<GFp5> {
+    let mut limbs = [GFp::ZERO; 5];
+    for (limb, chunk) in limbs.iter_mut().zip(buf.chunks_exact(8)) {
+        let value = u64::from_le_bytes(chunk.try_into().unwrap());
+        if value >= GFp::ORDER {
+            return None;
+        }
+        *limb = GFp::from_canonical_u64(value);
+    }
+    Some(QuinticExtension(limbs))
+}
+
+/// returns a uniformly random square in GFp5
+pub fn gfp5_sample_square<R: RngCore + ?Sized>(rng: &mut R) -> GFp5 {
+    GFp5::sample(rng).square()
+}
+
+/// returns a uniformly random non-square in GFp5
+pub fn gfp5_sample_non_square<R: RngCore + ?Sized>(rng: &mut R) -> GFp5 {
+    loop {
+        let attempt = GFp5::sample(rng);
+        if attempt.legendre() == -GFp::ONE {
+            return attempt;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use plonky2_field::types::Sample;
@@ -182,4 +222,29 @@ mod tests {
             assert!(!sqrt.sgn0())
         }
     }
+
+    #[test]
+    fn test_encode_decode() {
+        let mut rng = thread_rng();
+
+        for _ in 0..30 {
+            let x = GFp5::sample(&mut rng);
+            assert_eq!(gfp5_decode(&gfp5_encode(x)), Some(x));
+        }
+
+        // non-canonical limbs are rejected
+        let mut buf = gfp5_encode(GFp5::ZERO);
+        buf[16..24].copy_from_slice(&GFp::ORDER.to_le_bytes());
+        assert_eq!(gfp5_decode(&buf), None);
+    }
+
+    #[test]
+    fn test_sample_square_non_square() {
+        let mut rng = thread_rng();
+
+        for _ in 0..30 {
+            assert!(gfp5_sample_square(&mut rng).sqrt().is_some());
+            assert!(gfp5_sample_non_square(&mut rng).sqrt().is_none());
+        }
+    }
 }
@@ -0,0 +1,173 @@
+use plonky2_field::types::{Field, Sample};
+use rand::{Rng, RngCore};
+
+use super::curve::Point;
+use super::scalar_field::Scalar;
+use super::schnorr::{schnorr_challenge, SchnorrSignature};
+use super::GFp;
+
+// Blind Schnorr signatures, in the "clause" variant of Fuchsbauer, Plouviez and Seurin
+// (https://eprint.iacr.org/2019/877). Plain blind Schnorr signatures can be forged by a user
+// running a few hundred sessions concurrently (the ROS attack, https://eprint.iacr.org/2020/945).
+// In the clause variant, the signer commits to two nonces, the user blinds a challenge for
+// each of them, and the signer only answers one of the two challenges, picked at random.
+// This defeats the ROS attack, and the variant is secure under concurrent sessions assuming
+// that the modified ROS problem is hard.
+//
+// A session goes as follows, for i in {0, 1}:
+//   signer: k_i random, R_i = k_i * G                            -> SignerCommitment
+//   user:   a_i, b_i random, R'_i = R_i + a_i * G + b_i * pk,
+//           e'_i = hash(R'_i, pk, m), e_i = e'_i - b_i           -> BlindChallenge
+//   signer: j random, s_j = k_j - e_j * sk                       -> SignerResponse
+//   user:   checks R_j == s_j * G + e_j * pk; the signature is (s_j + a_j, e'_j)
+// and the result is a plain Schnorr signature of m, which the signer can't link to the session.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignerCommitment {
+    pub r: [Point; 2],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlindChallenge {
+    pub e: [Scalar; 2],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignerResponse {
+    /// index of the answered challenge, 0 or 1
+    pub clause: usize,
+    pub s: Scalar,
+}
+
+/// The state of the signer during a session. `respond` consumes it, so that its nonces are used only once.
+#[derive(Debug)]
+pub struct BlindSignerSession {
+    nonces: [Scalar; 2],
+}
+
+impl BlindSignerSession {
+    pub fn new<R: RngCore + ?Sized>(rng: &mut R) -> (Self, SignerCommitment) {
+        let nonces = [Scalar::sample(rng), Scalar::sample(rng)];
+        let commitment = SignerCommitment {
+            r: nonces.map(|k| Point::GENERATOR * k),
+        };
+        (Self { nonces }, commitment)
+    }
+
+    pub fn respond<R: RngCore + ?Sized>(self, sk: Scalar, challenge: &BlindChallenge, rng: &mut R) -> SignerResponse {
+        let clause = rng.gen_range(0..2);
+        SignerResponse {
+            clause,
+            s: self.nonces[clause] - challenge.e[clause] * sk,
+        }
+    }
+}
+
+/// The state of the user during a session, holding the blinding factors.
+#[derive(Debug)]
+pub struct BlindUserSession {
+    pk: Point,
+    commitment: SignerCommitment,
+    blinded_challenges: [Scalar; 2],
+    alphas: [Scalar; 2],
+}
+
+impl BlindUserSession {
+    pub fn new<R: RngCore + ?Sized>(
+        pk: Point,
+        commitment: SignerCommitment,
+        message: &[GFp],
+        rng: &mut R,
+    ) -> (Self, BlindChallenge) {
+        let mut alphas = [Scalar::ZERO; 2];
+        let mut blinded_challenges = [Scalar::ZERO; 2];
+        let mut challenges = [Scalar::ZERO; 2];
+        for i in 0..2 {
+            let (alpha, beta) = (Scalar::sample(rng), Scalar::sample(rng));
+            let r = commitment.r[i] + Point::GENERATOR * alpha + pk * beta;
+            alphas[i] = alpha;
+            blinded_challenges[i] = schnorr_challenge(r, pk, message);
+            challenges[i] = blinded_challenges[i] - beta;
+        }
+
+        let session = Self {
+            pk,
+            commitment,
+            blinded_challenges,
+            alphas,
+        };
+        (session, BlindChallenge { e: challenges })
+    }
+
+    /// Checks the response of the signer and unblinds it into a Schnorr signature of the message.
+    /// Returns `None` if the response is invalid.
+    pub fn unblind(self, challenge: &BlindChallenge, response: &SignerResponse) -> Option<SchnorrSignature> {
+        let j = response.clause;
+        if j > 1 {
+            return None;
+        }
+        if !(Point::GENERATOR * response.s + self.pk * challenge.e[j]).equals(self.commitment.r[j]) {
+            return None;
+        }
+
+        Some(SchnorrSignature {
+            s: response.s + self.alphas[j],
+            e: self.blinded_challenges[j],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::curve::schnorr::schnorr_verify;
+
+    #[test]
+    fn test_blind_schnorr() {
+        let mut rng = thread_rng();
+
+        let sk = Scalar::sample(&mut rng);
+        let pk = Point::GENERATOR * sk;
+        let message = [GFp::sample(&mut rng), GFp::sample(&mut rng)];
+
+        let mut clauses = [false; 2];
+        for _ in 0..16 {
+            let (signer, commitment) = BlindSignerSession::new(&mut rng);
+            let (user, challenge) = BlindUserSession::new(pk, commitment, &message, &mut rng);
+            let response = signer.respond(sk, &challenge, &mut rng);
+            clauses[response.clause] = true;
+
+            let sig = user.unblind(&challenge, &response).unwrap();
+            assert!(schnorr_verify(pk, &message, &sig));
+            assert!(!schnorr_verify(pk, &message[..1], &sig));
+
+            // the signer only sees blinded values
+            assert!(!commitment.r.iter().any(|&r| r.equals(Point::GENERATOR * sig.s + pk * sig.e)));
+            assert!(!challenge.e.contains(&sig.e));
+        }
+        assert_eq!(clauses, [true, true]);
+    }
+
+    #[test]
+    fn test_blind_schnorr_bad_response() {
+        let mut rng = thread_rng();
+
+        let sk = Scalar::sample(&mut rng);
+        let pk = Point::GENERATOR * sk;
+
+        // a tampered response
+        let (signer, commitment) = BlindSignerSession::new(&mut rng);
+        let (user, challenge) = BlindUserSession::new(pk, commitment, &[GFp::ONE], &mut rng);
+        let response = signer.respond(sk, &challenge, &mut rng);
+        let bad_response = SignerResponse { clause: response.clause, s: response.s + Scalar::ONE };
+        assert!(user.unblind(&challenge, &bad_response).is_none());
+
+        // the response for a wrong key
+        let (signer, commitment) = BlindSignerSession::new(&mut rng);
+        let (user, challenge) = BlindUserSession::new(pk, commitment, &[GFp::ONE], &mut rng);
+        let response = signer.respond(sk + Scalar::ONE, &challenge, &mut rng);
+        assert!(user.unblind(&challenge, &response).is_none());
+    }
+}
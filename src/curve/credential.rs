@@ -0,0 +1,336 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use plonky2_field::types::Sample;
+use rand::RngCore;
+
+use super::curve::Point;
+use super::hash_to_curve::hash_to_curve;
+use super::scalar_field::Scalar;
+use super::sigma::{LinearEquation, LinearProof, LinearRelation};
+
+// Keyed-verification anonymous credentials from the algebraic MAC MAC_GGM of Chase, Meiklejohn
+// and Zaverucha (https://eprint.iacr.org/2013/516), over attributes m_1, ..., m_n in the scalar field.
+//
+// The issuer has a secret key (x_0, x_1, ..., x_n) and publishes X_i = x_i * H, as well as the
+// Pedersen commitment C_x0 = x_0 * G + x0_blinding * H. H is a second generator, hashed to the curve
+// so that nobody knows its discrete logarithm to the base G.
+//
+// A credential on the attributes is a MAC (U, U') with U random and U' = (x_0 + sum x_i * m_i) * U.
+// The issuer proves that it is well formed with respect to the public parameters.
+//
+// To present the credential, the user re-randomizes it (U, U') -> (a * U, a * U'), and commits to
+// the attributes and to U' with random z_i and r:
+//   C_i = m_i * U + z_i * H,  C_U' = U' + r * G
+// then proves knowledge of m_i, z_i and r such that every C_i is well formed and V = sum z_i * X_i - r * G.
+// The issuer recomputes V = x_0 * U + sum x_i * C_i - C_U' with its secret key, which only gives
+// the right value if the committed attributes are the ones of a valid credential.
+//
+// The presentation proof is either a Sigma proof (`CredentialPresentation`), or a plonky2 proof made with
+// `CircuitBuilderCredential::verify_credential_presentation`, which can also prove facts about the
+// hidden attributes. In the latter case, the issuer checks that the V of the proof is `presentation_v`.
+
+pub(crate) const CREDENTIAL_GENERATOR_DOMAIN: &[u8] = b"ecgfp5-cmz-generator";
+pub(crate) const CREDENTIAL_ISSUANCE_DOMAIN: &[u8] = b"ecgfp5-cmz-issuance";
+pub(crate) const CREDENTIAL_PRESENTATION_DOMAIN: &[u8] = b"ecgfp5-cmz-presentation";
+
+/// The second generator H of the Pedersen commitments.
+pub fn credential_generator() -> Point {
+    hash_to_curve(CREDENTIAL_GENERATOR_DOMAIN, &[])
+}
+
+#[derive(Clone, Debug)]
+pub struct CredentialSecretKey {
+    pub x0: Scalar,
+    pub x0_blinding: Scalar,
+    pub x: Vec<Scalar>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuerParams {
+    pub c_x0: Point,
+    pub x: Vec<Point>,
+}
+
+/// A MAC on a list of attributes, kept by the user along with the attributes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Credential {
+    pub u: Point,
+    pub u_prime: Point,
+}
+
+/// The public part of a presentation: the re-randomized U, and the commitments to the attributes and to U'.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PresentationCommitments {
+    pub u: Point,
+    pub attribute_commitments: Vec<Point>,
+    pub c_u_prime: Point,
+}
+
+/// The secrets of a presentation: the attributes and the blinding factors of the commitments.
+#[derive(Clone, Debug)]
+pub struct PresentationOpening {
+    pub attributes: Vec<Scalar>,
+    pub blindings: Vec<Scalar>,
+    pub r: Scalar,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CredentialPresentation {
+    pub commitments: PresentationCommitments,
+    pub proof: LinearProof,
+}
+
+impl CredentialSecretKey {
+    pub fn sample<R: RngCore + ?Sized>(num_attributes: usize, rng: &mut R) -> Self {
+        Self {
+            x0: Scalar::sample(rng),
+            x0_blinding: Scalar::sample(rng),
+            x: (0..num_attributes).map(|_| Scalar::sample(rng)).collect(),
+        }
+    }
+
+    pub fn params(&self) -> IssuerParams {
+        let h = credential_generator();
+        IssuerParams {
+            c_x0: Point::GENERATOR * self.x0 + h * self.x0_blinding,
+            x: self.x.iter().map(|&x_i| h * x_i).collect(),
+        }
+    }
+
+    fn mac_key(&self, attributes: &[Scalar]) -> Scalar {
+        assert_eq!(attributes.len(), self.x.len());
+        self.x
+            .iter()
+            .zip(attributes.iter())
+            .fold(self.x0, |acc, (&x_i, &m_i)| acc + x_i * m_i)
+    }
+
+    /// Issues a credential on `attributes`, with a proof that it is well formed.
+    pub fn issue<R: RngCore + ?Sized>(&self, attributes: &[Scalar], rng: &mut R) -> (Credential, LinearProof) {
+        let u = Point::GENERATOR * Scalar::sample(rng);
+        let credential = Credential {
+            u,
+            u_prime: u * self.mac_key(attributes),
+        };
+
+        let relation = issuance_relation(&self.params(), attributes, &credential);
+        let mut secrets = vec![self.x0, self.x0_blinding];
+        secrets.extend_from_slice(&self.x);
+        let proof = relation.prove(CREDENTIAL_ISSUANCE_DOMAIN, &secrets, rng);
+
+        (credential, proof)
+    }
+
+    pub fn verify_mac(&self, credential: &Credential, attributes: &[Scalar]) -> bool {
+        attributes.len() == self.x.len()
+            && !credential.u.is_neutral()
+            && credential.u_prime.equals(credential.u * self.mac_key(attributes))
+    }
+
+    /// Recomputes V = x_0 * U + sum x_i * C_i - C_U', which is sum z_i * X_i - r * G for a valid presentation.
+    /// Returns `None` if the commitments are malformed.
+    pub fn presentation_v(&self, commitments: &PresentationCommitments) -> Option<Point> {
+        if commitments.attribute_commitments.len() != self.x.len() || commitments.u.is_neutral() {
+            return None;
+        }
+
+        let v = self
+            .x
+            .iter()
+            .zip(commitments.attribute_commitments.iter())
+            .fold(commitments.u * self.x0, |acc, (&x_i, &c_i)| acc + c_i * x_i);
+        Some(v - commitments.c_u_prime)
+    }
+
+    pub fn verify_presentation(&self, presentation: &CredentialPresentation) -> bool {
+        match self.presentation_v(&presentation.commitments) {
+            Some(v) => presentation_relation(&self.params(), &presentation.commitments, v)
+                .verify(CREDENTIAL_PRESENTATION_DOMAIN, &presentation.proof),
+            None => false,
+        }
+    }
+}
+
+impl Credential {
+    /// Checks the issuance proof, on the user side.
+    pub fn verify_issuance(&self, params: &IssuerParams, attributes: &[Scalar], proof: &LinearProof) -> bool {
+        attributes.len() == params.x.len()
+            && !self.u.is_neutral()
+            && issuance_relation(params, attributes, self).verify(CREDENTIAL_ISSUANCE_DOMAIN, proof)
+    }
+
+    /// Re-randomizes the credential and commits to its attributes, for a presentation.
+    pub fn commit<R: RngCore + ?Sized>(
+        &self,
+        attributes: &[Scalar],
+        rng: &mut R,
+    ) -> (PresentationCommitments, PresentationOpening) {
+        let h = credential_generator();
+        let a = Scalar::sample(rng);
+        let (u, u_prime) = (self.u * a, self.u_prime * a);
+
+        let blindings = attributes.iter().map(|_| Scalar::sample(rng)).collect::<Vec<_>>();
+        let r = Scalar::sample(rng);
+        let commitments = PresentationCommitments {
+            u,
+            attribute_commitments: attributes
+                .iter()
+                .zip(blindings.iter())
+                .map(|(&m_i, &z_i)| u * m_i + h * z_i)
+                .collect(),
+            c_u_prime: u_prime + Point::GENERATOR * r,
+        };
+        let opening = PresentationOpening {
+            attributes: attributes.to_vec(),
+            blindings,
+            r,
+        };
+
+        (commitments, opening)
+    }
+
+    /// Presents the credential, hiding all of the attributes.
+    pub fn present<R: RngCore + ?Sized>(
+        &self,
+        params: &IssuerParams,
+        attributes: &[Scalar],
+        rng: &mut R,
+    ) -> CredentialPresentation {
+        let (commitments, opening) = self.commit(attributes, rng);
+
+        let relation = presentation_relation(params, &commitments, opening.v(params));
+        let mut secrets = opening.attributes.clone();
+        secrets.extend_from_slice(&opening.blindings);
+        secrets.push(opening.r);
+        let proof = relation.prove(CREDENTIAL_PRESENTATION_DOMAIN, &secrets, rng);
+
+        CredentialPresentation { commitments, proof }
+    }
+}
+
+impl PresentationOpening {
+    /// Computes V = sum z_i * X_i - r * G, on the user side.
+    pub fn v(&self, params: &IssuerParams) -> Point {
+        params
+            .x
+            .iter()
+            .zip(self.blindings.iter())
+            .fold(-(Point::GENERATOR * self.r), |acc, (&x_i, &z_i)| acc + x_i * z_i)
+    }
+}
+
+// secrets: x_0, x0_blinding, x_1, ..., x_n
+//   C_x0 = x_0 * G + x0_blinding * H, X_i = x_i * H, U' = x_0 * U + sum x_i * (m_i * U)
+fn issuance_relation(params: &IssuerParams, attributes: &[Scalar], credential: &Credential) -> LinearRelation {
+    let h = credential_generator();
+    let n = params.x.len();
+
+    let mut equations = vec![LinearEquation {
+        terms: vec![(0, Point::GENERATOR), (1, h)],
+        image: params.c_x0,
+    }];
+    equations.extend(params.x.iter().enumerate().map(|(i, &x_i)| LinearEquation {
+        terms: vec![(2 + i, h)],
+        image: x_i,
+    }));
+    let mut terms = vec![(0, credential.u)];
+    terms.extend(attributes.iter().enumerate().map(|(i, &m_i)| (2 + i, credential.u * m_i)));
+    equations.push(LinearEquation {
+        terms,
+        image: credential.u_prime,
+    });
+
+    LinearRelation {
+        num_secrets: 2 + n,
+        equations,
+    }
+}
+
+// secrets: m_1, ..., m_n, z_1, ..., z_n, r
+//   C_i = m_i * U + z_i * H, V = sum z_i * X_i - r * G
+fn presentation_relation(params: &IssuerParams, commitments: &PresentationCommitments, v: Point) -> LinearRelation {
+    let h = credential_generator();
+    let n = params.x.len();
+
+    let mut equations = commitments
+        .attribute_commitments
+        .iter()
+        .enumerate()
+        .map(|(i, &c_i)| LinearEquation {
+            terms: vec![(i, commitments.u), (n + i, h)],
+            image: c_i,
+        })
+        .collect::<Vec<_>>();
+    let mut terms = params.x.iter().enumerate().map(|(i, &x_i)| (n + i, x_i)).collect::<Vec<_>>();
+    terms.push((2 * n, -Point::GENERATOR));
+    equations.push(LinearEquation { terms, image: v });
+
+    LinearRelation {
+        num_secrets: 2 * n + 1,
+        equations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2_field::types::Field;
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn test_credential_issuance() {
+        let mut rng = thread_rng();
+
+        let sk = CredentialSecretKey::sample(3, &mut rng);
+        let params = sk.params();
+        let attributes = (0..3).map(|_| Scalar::sample(&mut rng)).collect::<Vec<_>>();
+
+        let (credential, proof) = sk.issue(&attributes, &mut rng);
+        assert!(credential.verify_issuance(&params, &attributes, &proof));
+        assert!(sk.verify_mac(&credential, &attributes));
+
+        let mut other_attributes = attributes.clone();
+        other_attributes[1] += Scalar::ONE;
+        assert!(!credential.verify_issuance(&params, &other_attributes, &proof));
+        assert!(!sk.verify_mac(&credential, &other_attributes));
+        let other_params = CredentialSecretKey::sample(3, &mut rng).params();
+        assert!(!credential.verify_issuance(&other_params, &attributes, &proof));
+    }
+
+    #[test]
+    fn test_credential_presentation() {
+        let mut rng = thread_rng();
+
+        let sk = CredentialSecretKey::sample(2, &mut rng);
+        let params = sk.params();
+        let attributes = vec![Scalar::from_canonical_u64(1990), Scalar::sample(&mut rng)];
+        let (credential, _) = sk.issue(&attributes, &mut rng);
+
+        let presentation = credential.present(&params, &attributes, &mut rng);
+        assert!(sk.verify_presentation(&presentation));
+
+        // presentations are unlinkable to the credential
+        assert!(!presentation.commitments.u.equals(credential.u));
+
+        // the user side V matches the issuer side one
+        let (commitments, opening) = credential.commit(&attributes, &mut rng);
+        assert!(sk.presentation_v(&commitments).unwrap().equals(opening.v(&params)));
+
+        // a credential from another issuer, or on other attributes
+        let other_sk = CredentialSecretKey::sample(2, &mut rng);
+        let (other_credential, _) = other_sk.issue(&attributes, &mut rng);
+        assert!(!sk.verify_presentation(&other_credential.present(&params, &attributes, &mut rng)));
+        let mut other_attributes = attributes.clone();
+        other_attributes[0] = Scalar::from_canonical_u64(2000);
+        assert!(!sk.verify_presentation(&credential.present(&params, &other_attributes, &mut rng)));
+
+        // the trivial MAC (0, 0)
+        let neutral = Credential {
+            u: Point::NEUTRAL,
+            u_prime: Point::NEUTRAL,
+        };
+        assert!(!sk.verify_mac(&neutral, &attributes));
+        assert!(!sk.verify_presentation(&neutral.present(&params, &attributes, &mut rng)));
+    }
+}
@@ -0,0 +1,117 @@
+use plonky2::hash::hashing::hash_n_to_m_no_pad;
+use plonky2::hash::poseidon::PoseidonPermutation;
+use plonky2_field::extension::quintic::QuinticExtension;
+use plonky2_field::goldilocks_field::GoldilocksField;
+use plonky2_field::ops::Square;
+use plonky2_field::types::Field;
+
+use super::base_field::{InverseOrZero, Legendre, Sgn0, SquareRoot};
+use super::curve::{Point, WeierstrassPoint};
+use super::scalar_field::hash_to_scalar_prefix;
+use super::{GFp, GFp5};
+
+/// The `Z` constant of the simplified SWU map: the smallest integer that is not a square
+/// in GFp5 and such that g(B / (Z * A)) is a square, where g(x) = x^3 + A*x + B is the
+/// short Weierstrass equation of the curve.
+pub(crate) const SSWU_Z: GFp5 = QuinticExtension([
+    GoldilocksField(13),
+    GFp::ZERO,
+    GFp::ZERO,
+    GFp::ZERO,
+    GFp::ZERO,
+]);
+
+/// Number of field elements squeezed by `hash_to_curve`: two GFp5 elements.
+pub(crate) const HASH_TO_CURVE_NUM_OUTPUTS: usize = 10;
+
+/// Maps a field element to a curve point with the simplified SWU map (RFC 9380, section 6.6.2),
+/// applied to the short Weierstrass form of the curve.
+///
+/// The point R = (x, y) found by the map may lie outside the subgroup of order n. In that case
+/// R + N is returned instead, where N is the point of order 2, so that doubling the result gives
+/// 2 * R either way; this is what the `curve_map_to_curve` gadget relies on.
+pub fn map_to_curve(u: GFp5) -> Point {
+    let (a, b) = (WeierstrassPoint::A, WeierstrassPoint::B);
+    let g = |x: GFp5| x * x.square() + a * x + b;
+
+    let z_u2 = SSWU_Z * u.square();
+    let tv1 = (z_u2.square() + z_u2).inverse_or_zero();
+    let x1 = if tv1 == GFp5::ZERO {
+        b / (SSWU_Z * a)
+    } else {
+        -b / a * (GFp5::ONE + tv1)
+    };
+    let x2 = z_u2 * x1;
+
+    // exactly one of g(x1) and g(x2) is a square, since g(x2) = (Z * u^2)^3 * g(x1)
+    let (x, y) = match g(x1).sqrt() {
+        Some(y) => (x1, y),
+        None => (x2, g(x2).sqrt().expect("g(x2) is a square when g(x1) is not")),
+    };
+    let y = if u.sgn0() != y.sgn0() { -y } else { y };
+
+    // R is in the subgroup iff its x coordinate on y^2 = x * (x^2 + a*x + b) is a square.
+    // Since w(-R + N) = w(R) = -w(R + N), negate the encoding of R otherwise.
+    let x = x - Point::A / GFp5::from_canonical_u16(3);
+    let w = -y * x.inverse_or_zero();
+    let w = if x.legendre() == -GFp::ONE { -w } else { w };
+    Point::decode(w).expect("the encoding of a curve point always decodes")
+}
+
+/// Hashes `inputs` to a curve point, under the domain separation tag `domain`, such that
+/// nobody knows the discrete logarithm of the result. Two GFp5 elements u0 and u1 are
+/// squeezed from Poseidon (with the same framing as `hash_to_scalar`), and the result is
+/// 2 * (map_to_curve(u0) + map_to_curve(u1)), the doubling matching the cofactor clearing
+/// done by the `curve_hash_to_curve` gadget.
+pub fn hash_to_curve(domain: &[u8], inputs: &[GFp]) -> Point {
+    let mut message = hash_to_scalar_prefix(domain, inputs.len());
+    message.extend_from_slice(inputs);
+    let outputs = hash_n_to_m_no_pad::<GFp, PoseidonPermutation>(&message, HASH_TO_CURVE_NUM_OUTPUTS);
+
+    let u0 = QuinticExtension([outputs[0], outputs[1], outputs[2], outputs[3], outputs[4]]);
+    let u1 = QuinticExtension([outputs[5], outputs[6], outputs[7], outputs[8], outputs[9]]);
+    (map_to_curve(u0) + map_to_curve(u1)).double()
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2_field::types::Sample;
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn test_sswu_z() {
+        let (a, b) = (WeierstrassPoint::A, WeierstrassPoint::B);
+        let x = b / (SSWU_Z * a);
+
+        assert_eq!(SSWU_Z.legendre(), -GFp::ONE);
+        assert_eq!((x * x.square() + a * x + b).legendre(), GFp::ONE);
+    }
+
+    #[test]
+    fn test_map_to_curve() {
+        let mut rng = thread_rng();
+
+        for _ in 0..32 {
+            let u = GFp5::sample(&mut rng);
+            let p = map_to_curve(u);
+            assert!(!p.is_neutral());
+            assert!(p.equals(map_to_curve(u)));
+        }
+
+        // u = 0 is the exceptional case of the map
+        map_to_curve(GFp5::ZERO);
+    }
+
+    #[test]
+    fn test_hash_to_curve() {
+        let inputs = [GFp::ONE, GFp::TWO];
+
+        let p = hash_to_curve(b"test", &inputs);
+        assert!(p.equals(hash_to_curve(b"test", &inputs)));
+        assert!(!p.equals(hash_to_curve(b"other", &inputs)));
+        assert!(!p.equals(hash_to_curve(b"test", &inputs[..1])));
+        assert!(!p.is_neutral());
+    }
+}
@@ -0,0 +1,195 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use plonky2_field::types::Field;
+
+use super::curve::Point;
+use super::scalar_field::{bytes_to_u32_elements, hash_to_scalar, hash_with_prefix, Scalar};
+use super::GFp;
+
+// Hierarchical deterministic key derivation, following the structure of BIP32.
+// HMAC-SHA512 is replaced by two Poseidon hashes sharing the same inputs: one is
+// reduced to the scalar tweak (with `hash_to_scalar`), the other gives the
+// chain code of the child. A child secret key is the parent's plus the tweak, and
+// the child public key is the parent's plus tweak*G, so non-hardened children can
+// be derived from the public key alone, in or out of circuit.
+
+const MASTER_KEY_DOMAIN: &[u8] = b"ecgfp5-hd-master-key";
+const MASTER_CHAIN_CODE_DOMAIN: &[u8] = b"ecgfp5-hd-master-chain-code";
+pub(crate) const CHILD_TWEAK_DOMAIN: &[u8] = b"ecgfp5-hd-child-tweak";
+pub(crate) const CHILD_CHAIN_CODE_DOMAIN: &[u8] = b"ecgfp5-hd-child-chain-code";
+
+/// Child indices at or above this offset are hardened.
+pub const HARDENED_OFFSET: u32 = 1 << 31;
+
+/// Number of field elements of a chain code.
+pub const CHAIN_CODE_LEN: usize = 4;
+
+/// The chain code of an extended key, i.e. the key of the PRF used to derive its children.
+pub type ChainCode = [GFp; CHAIN_CODE_LEN];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExtendedSecretKey {
+    pub sk: Scalar,
+    pub chain_code: ChainCode,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ExtendedPublicKey {
+    pub pk: Point,
+    pub chain_code: ChainCode,
+}
+
+impl ExtendedSecretKey {
+    /// Derives the master key from `seed`, which should contain at least 128 bits of entropy.
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let mut inputs = vec![GFp::from_canonical_usize(seed.len())];
+        inputs.extend(bytes_to_u32_elements(seed));
+
+        Self {
+            sk: hash_to_scalar(MASTER_KEY_DOMAIN, &inputs),
+            chain_code: hash_to_chain_code(MASTER_CHAIN_CODE_DOMAIN, &inputs),
+        }
+    }
+
+    pub fn public_key(&self) -> ExtendedPublicKey {
+        ExtendedPublicKey {
+            pk: Point::GENERATOR * self.sk,
+            chain_code: self.chain_code,
+        }
+    }
+
+    /// Derives the child at `index`. Hardened children (`index >= HARDENED_OFFSET`) are derived
+    /// from the secret key; the others from the public key, so that they match
+    /// `ExtendedPublicKey::derive_child`.
+    pub fn derive_child(&self, index: u32) -> Self {
+        let (tweak, chain_code) = if index >= HARDENED_OFFSET {
+            child_tweak(&self.chain_code, &bytes_to_u32_elements(&self.sk.encode()), index)
+        } else {
+            let pk = Point::GENERATOR * self.sk;
+            child_tweak(&self.chain_code, &pk.encode().0, index)
+        };
+
+        Self {
+            sk: self.sk + tweak,
+            chain_code,
+        }
+    }
+
+    pub fn derive_path(&self, path: &DerivationPath) -> Self {
+        path.0.iter().fold(*self, |key, &index| key.derive_child(index))
+    }
+}
+
+impl ExtendedPublicKey {
+    /// Derives the non-hardened child at `index`. Returns `None` if `index` is hardened.
+    pub fn derive_child(&self, index: u32) -> Option<Self> {
+        if index >= HARDENED_OFFSET {
+            return None;
+        }
+
+        let (tweak, chain_code) = child_tweak(&self.chain_code, &self.pk.encode().0, index);
+        Some(Self {
+            pk: self.pk + Point::GENERATOR * tweak,
+            chain_code,
+        })
+    }
+
+    /// Derives the key at `path`. Returns `None` if `path` contains a hardened index.
+    pub fn derive_path(&self, path: &DerivationPath) -> Option<Self> {
+        path.0.iter().try_fold(*self, |key, &index| key.derive_child(index))
+    }
+}
+
+/// A sequence of child indices, e.g. `m/44'/0'/0/1`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DerivationPath(pub Vec<u32>);
+
+impl DerivationPath {
+    /// Parses a path of the form `m/44'/0'/0/1`; hardened indices are marked with `'` or `h`.
+    /// Returns `None` if the path is malformed or an index is out of range.
+    pub fn parse(path: &str) -> Option<Self> {
+        let mut components = path.split('/');
+        if components.next()? != "m" {
+            return None;
+        }
+
+        components
+            .map(|component| {
+                let (index, hardened) = match component.strip_suffix(['\'', 'h']) {
+                    Some(index) => (index, true),
+                    None => (component, false),
+                };
+                let index = index.parse::<u32>().ok().filter(|&i| i < HARDENED_OFFSET)?;
+                Some(if hardened { index + HARDENED_OFFSET } else { index })
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Self)
+    }
+}
+
+// Hashes the parent chain code, the parent key data (the public key encoding, or the
+// secret key bytes for hardened children) and the index into the tweak and the child chain code.
+fn child_tweak(chain_code: &ChainCode, key_data: &[GFp], index: u32) -> (Scalar, ChainCode) {
+    let mut inputs = chain_code.to_vec();
+    inputs.extend_from_slice(key_data);
+    inputs.push(GFp::from_canonical_u32(index));
+
+    (
+        hash_to_scalar(CHILD_TWEAK_DOMAIN, &inputs),
+        hash_to_chain_code(CHILD_CHAIN_CODE_DOMAIN, &inputs),
+    )
+}
+
+// Same framing as `hash_to_scalar`, but only squeezes the elements of a chain code.
+// The `hd_derive_public_child` gadget computes the same function.
+pub(crate) fn hash_to_chain_code(domain: &[u8], inputs: &[GFp]) -> ChainCode {
+    let outputs = hash_with_prefix(domain, inputs, CHAIN_CODE_LEN);
+    core::array::from_fn(|i| outputs[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    #[test]
+    fn test_derive_child() {
+        let mut rng = thread_rng();
+        let seed: [u8; 32] = rng.gen();
+
+        let master = ExtendedSecretKey::from_seed(&seed);
+        assert_eq!(master, ExtendedSecretKey::from_seed(&seed));
+        assert_ne!(master, ExtendedSecretKey::from_seed(&seed[..31]));
+
+        // non-hardened children can be derived from the public key
+        let path = DerivationPath::parse("m/0/7/42").unwrap();
+        let child = master.derive_path(&path);
+        let child_pub = master.public_key().derive_path(&path).unwrap();
+        assert!(child_pub.pk.equals(Point::GENERATOR * child.sk));
+        assert_eq!(child_pub.chain_code, child.chain_code);
+
+        // hardened ones can't
+        let path = DerivationPath::parse("m/0/7'").unwrap();
+        assert!(master.public_key().derive_path(&path).is_none());
+        let hardened = master.derive_path(&path);
+        assert_ne!(hardened, master.derive_path(&DerivationPath::parse("m/0/7").unwrap()));
+        assert_ne!(hardened.sk, child.sk);
+    }
+
+    #[test]
+    fn test_parse_derivation_path() {
+        assert_eq!(DerivationPath::parse("m"), Some(DerivationPath(vec![])));
+        assert_eq!(
+            DerivationPath::parse("m/44'/0h/1/2147483647"),
+            Some(DerivationPath(vec![44 + HARDENED_OFFSET, HARDENED_OFFSET, 1, HARDENED_OFFSET - 1]))
+        );
+
+        assert_eq!(DerivationPath::parse(""), None);
+        assert_eq!(DerivationPath::parse("44/0"), None);
+        assert_eq!(DerivationPath::parse("m/"), None);
+        assert_eq!(DerivationPath::parse("m/2147483648"), None);
+        assert_eq!(DerivationPath::parse("m/1''"), None);
+        assert_eq!(DerivationPath::parse("m/-1"), None);
+    }
+}
@@ -1,12 +1,26 @@
 use plonky2_field::{extension::quintic::QuinticExtension, goldilocks_field::GoldilocksField};
 
-pub(crate) type GFp5 = QuinticExtension<GoldilocksField>;
-pub(crate) type GFp = GoldilocksField;
+/// The base field of the curve, GF(p^5) with p = 2^64 - 2^32 + 1
+pub type GFp5 = QuinticExtension<GoldilocksField>;
+/// The Goldilocks field GF(p)
+pub type GFp = GoldilocksField;
 
+pub mod adaptor;
 pub mod base_field;
+pub mod blind_schnorr;
+pub mod credential;
 pub mod curve;
+pub mod hash_to_curve;
+pub mod hd;
 pub(crate) mod mul_table;
+pub mod nonce;
+pub mod nullifier;
+pub mod polynomial;
+pub mod ring;
 pub mod scalar_field;
+pub mod schnorr;
+pub mod sigma;
+pub mod stealth;
 
 #[cfg(test)]
 pub mod test_utils;
@@ -0,0 +1,59 @@
+use plonky2_field::types::Field;
+
+use super::scalar_field::{bytes_to_u32_elements, hash_to_scalar, Scalar};
+use super::GFp;
+
+/// Derives a signing nonce from the secret key `sk` and the (digest of the) `message`,
+/// in the spirit of RFC 6979. The nonce is `hash_to_scalar` of the key and the message under
+/// the domain separation tag `domain`, so it is unbiased.
+///
+/// `domain` must be unique to the protocol using the nonce: a nonce reused across two protocols
+/// that answer different challenges with it leaks the key, and the protocols may well put the
+/// same inputs in `message`. Within one domain, the same key and message always give the same nonce.
+///
+/// Without `extra_entropy`, signing is deterministic: a broken or predictable RNG can't leak the
+/// key through repeated or biased nonces, and signatures are reproducible in tests.
+/// If `extra_entropy` is given, it is hashed in as well ("hedged" signing): the nonce stays safe
+/// if either the RNG or the hash is sound, and fault attacks, which glitch a signer into answering
+/// a different challenge with the nonce it already used for a message, don't get the same nonce twice.
+pub fn derive_nonce(domain: &[u8], sk: Scalar, message: &[GFp], extra_entropy: Option<&[u8; 32]>) -> Scalar {
+    let mut inputs = bytes_to_u32_elements(&sk.encode());
+    match extra_entropy {
+        Some(entropy) => {
+            inputs.push(GFp::ONE);
+            inputs.extend(bytes_to_u32_elements(entropy));
+        }
+        None => inputs.push(GFp::ZERO),
+    }
+    inputs.extend_from_slice(message);
+
+    hash_to_scalar(domain, &inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2_field::types::Sample;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    #[test]
+    fn test_derive_nonce() {
+        let mut rng = thread_rng();
+
+        let sk = Scalar::sample(&mut rng);
+        let m = [GFp::sample(&mut rng), GFp::sample(&mut rng)];
+        let entropy: [u8; 32] = rng.gen();
+
+        // deterministic without extra entropy
+        let k = derive_nonce(b"test", sk, &m, None);
+        assert_eq!(k, derive_nonce(b"test", sk, &m, None));
+
+        // depends on every input
+        assert_ne!(k, derive_nonce(b"test", sk + Scalar::ONE, &m, None));
+        assert_ne!(k, derive_nonce(b"test", sk, &m[..1], None));
+        assert_ne!(k, derive_nonce(b"test", sk, &m, Some(&entropy)));
+        assert_ne!(derive_nonce(b"test", sk, &m, Some(&entropy)), derive_nonce(b"test", sk, &m, Some(&[0; 32])));
+        assert_ne!(k, derive_nonce(b"other", sk, &m, None));
+    }
+}
@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+
+use super::curve::Point;
+use super::hash_to_curve::hash_to_curve;
+use super::nonce::derive_nonce;
+use super::scalar_field::{hash_to_scalar, Scalar};
+use super::GFp;
+
+// Deterministic nullifiers in the style of PLUME (https://eprint.iacr.org/2022/1255).
+// The nullifier of a key pair (sk, pk) for a message m is sk * H(m, pk), where H hashes
+// to the curve. It is unique for every (pk, m), and unlinkable to pk without sk.
+// It comes with a proof that log_G(pk) == log_H(nullifier) (a Chaum-Pedersen DLEQ proof):
+//   r1 = r * G, r2 = r * H, c = hash(pk, H, nullifier, r1, r2), s = r + c * sk
+// which is checked by recomputing r1 = s * G - c * pk and r2 = s * H - c * nullifier.
+
+pub(crate) const NULLIFIER_HASH_DOMAIN: &[u8] = b"ecgfp5-plume-hash";
+pub(crate) const NULLIFIER_CHALLENGE_DOMAIN: &[u8] = b"ecgfp5-plume-challenge";
+const NULLIFIER_NONCE_DOMAIN: &[u8] = b"ecgfp5-plume-nonce";
+
+/// The DLEQ proof accompanying a nullifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NullifierProof {
+    pub c: Scalar,
+    pub s: Scalar,
+}
+
+/// Returns H(m, pk), the point that the secret key is multiplied with to get the nullifier.
+pub fn nullifier_base(pk: Point, message: &[GFp]) -> Point {
+    let mut inputs = pk.encode().0.to_vec();
+    inputs.extend_from_slice(message);
+    hash_to_curve(NULLIFIER_HASH_DOMAIN, &inputs)
+}
+
+pub fn nullifier(sk: Scalar, message: &[GFp]) -> Point {
+    nullifier_base(Point::GENERATOR * sk, message) * sk
+}
+
+/// Computes the nullifier of `sk` for `message`, and a proof that it is well formed.
+/// The proof nonce is derived deterministically with `derive_nonce`, in a domain of its own, so that it never
+/// matches the nonce of a Schnorr signature made with the same key.
+pub fn prove_nullifier(sk: Scalar, message: &[GFp]) -> (Point, NullifierProof) {
+    let pk = Point::GENERATOR * sk;
+    let h = nullifier_base(pk, message);
+    let nullifier = h * sk;
+
+    let mut nonce_inputs = h.encode().0.to_vec();
+    nonce_inputs.extend_from_slice(message);
+    let r = derive_nonce(NULLIFIER_NONCE_DOMAIN, sk, &nonce_inputs, None);
+
+    let c = nullifier_challenge(pk, h, nullifier, Point::GENERATOR * r, h * r);
+    let s = r + c * sk;
+
+    (nullifier, NullifierProof { c, s })
+}
+
+/// Checks that `nullifier` is the nullifier of the secret key of `pk` for `message`.
+pub fn verify_nullifier(pk: Point, message: &[GFp], nullifier: Point, proof: &NullifierProof) -> bool {
+    let h = nullifier_base(pk, message);
+    let r1 = Point::GENERATOR * proof.s - pk * proof.c;
+    let r2 = h * proof.s - nullifier * proof.c;
+
+    nullifier_challenge(pk, h, nullifier, r1, r2) == proof.c
+}
+
+fn nullifier_challenge(pk: Point, h: Point, nullifier: Point, r1: Point, r2: Point) -> Scalar {
+    let inputs = [pk, h, nullifier, r1, r2]
+        .iter()
+        .flat_map(|p| p.encode().0)
+        .collect::<Vec<_>>();
+    hash_to_scalar(NULLIFIER_CHALLENGE_DOMAIN, &inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2_field::types::{Field, Sample};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::curve::schnorr::schnorr_sign;
+
+    #[test]
+    fn test_nullifier() {
+        let mut rng = thread_rng();
+
+        let sk = Scalar::sample(&mut rng);
+        let pk = Point::GENERATOR * sk;
+        let message = [GFp::sample(&mut rng), GFp::sample(&mut rng)];
+
+        let (nf, proof) = prove_nullifier(sk, &message);
+        assert!(verify_nullifier(pk, &message, nf, &proof));
+
+        // the nullifier is deterministic, and differs across messages and keys
+        assert!(nf.equals(nullifier(sk, &message)));
+        assert!(!nf.equals(nullifier(sk, &message[..1])));
+        assert!(!nf.equals(nullifier(sk + Scalar::ONE, &message)));
+
+        // wrong nullifier, key, message or proof
+        let other_pk = Point::sample(&mut rng);
+        assert!(!verify_nullifier(pk, &message, nf + Point::GENERATOR, &proof));
+        assert!(!verify_nullifier(other_pk, &message, nf, &proof));
+        assert!(!verify_nullifier(pk, &message[..1], nf, &proof));
+        let bad_proof = NullifierProof { c: proof.c, s: proof.s + Scalar::ONE };
+        assert!(!verify_nullifier(pk, &message, nf, &bad_proof));
+    }
+
+    #[test]
+    fn test_nullifier_nonce_differs_from_schnorr() {
+        let mut rng = thread_rng();
+
+        let sk = Scalar::sample(&mut rng);
+        let pk = Point::GENERATOR * sk;
+        let message = [GFp::sample(&mut rng), GFp::sample(&mut rng)];
+
+        // a Schnorr signature on the nonce inputs of the proof must not reuse its nonce,
+        // or s_sig + e * sk == s_nf - c * sk would reveal sk
+        let (_, proof) = prove_nullifier(sk, &message);
+        let mut nonce_inputs = nullifier_base(pk, &message).encode().0.to_vec();
+        nonce_inputs.extend_from_slice(&message);
+        let sig = schnorr_sign(sk, &nonce_inputs);
+
+        assert_ne!(proof.s - proof.c * sk, sig.s + sig.e * sk);
+        assert_ne!((proof.s - sig.s) / (proof.c + sig.e), sk);
+    }
+}
@@ -0,0 +1,134 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use plonky2_field::types::Field;
+
+use super::GFp5;
+
+// polynomials are represented by their coefficients, lowest degree first
+
+/// evaluates the polynomial `coeffs` at `x` using Horner's rule
+pub fn poly_eval(coeffs: &[GFp5], x: GFp5) -> GFp5 {
+    coeffs.iter().rev().fold(GFp5::ZERO, |acc, &c| acc * x + c)
+}
+
+pub fn poly_add(a: &[GFp5], b: &[GFp5]) -> Vec<GFp5> {
+    let mut res = vec![GFp5::ZERO; a.len().max(b.len())];
+    for (i, &c) in a.iter().enumerate() {
+        res[i] += c;
+    }
+    for (i, &c) in b.iter().enumerate() {
+        res[i] += c;
+    }
+
+    res
+}
+
+pub fn poly_mul(a: &[GFp5], b: &[GFp5]) -> Vec<GFp5> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let mut res = vec![GFp5::ZERO; a.len() + b.len() - 1];
+    for (i, &a_i) in a.iter().enumerate() {
+        for (j, &b_j) in b.iter().enumerate() {
+            res[i + j] += a_i * b_j;
+        }
+    }
+
+    res
+}
+
+/// returns the coefficients of the Lagrange basis polynomials for the points `xs`,
+/// i.e. L_i such that L_i(xs[i]) = 1 and L_i(xs[j]) = 0 for j != i.
+/// Panics if the points are not distinct.
+pub fn lagrange_basis(xs: &[GFp5]) -> Vec<Vec<GFp5>> {
+    xs.iter()
+        .enumerate()
+        .map(|(i, &x_i)| {
+            let mut numerator = vec![GFp5::ONE];
+            let mut denominator = GFp5::ONE;
+            for (j, &x_j) in xs.iter().enumerate() {
+                if i != j {
+                    numerator = poly_mul(&numerator, &[-x_j, GFp5::ONE]);
+                    denominator *= x_i - x_j;
+                }
+            }
+
+            let denominator_inv = denominator.try_inverse().expect("interpolation points must be distinct");
+            numerator.into_iter().map(|c| c * denominator_inv).collect()
+        })
+        .collect()
+}
+
+/// returns the Lagrange coefficients L_i(at) for the points `xs`, so that p(at) = sum(L_i(at) * p(xs[i]))
+/// for any polynomial p of degree less than `xs.len()`. Panics if the points are not distinct.
+pub fn lagrange_coefficients(xs: &[GFp5], at: GFp5) -> Vec<GFp5> {
+    xs.iter()
+        .enumerate()
+        .map(|(i, &x_i)| {
+            let mut numerator = GFp5::ONE;
+            let mut denominator = GFp5::ONE;
+            for (j, &x_j) in xs.iter().enumerate() {
+                if i != j {
+                    numerator *= at - x_j;
+                    denominator *= x_i - x_j;
+                }
+            }
+
+            numerator * denominator.try_inverse().expect("interpolation points must be distinct")
+        })
+        .collect()
+}
+
+/// returns the unique polynomial of degree less than `xs.len()` such that p(xs[i]) = ys[i].
+/// Panics if the points are not distinct.
+pub fn poly_interpolate(xs: &[GFp5], ys: &[GFp5]) -> Vec<GFp5> {
+    assert_eq!(xs.len(), ys.len());
+
+    let mut res = vec![GFp5::ZERO; xs.len()];
+    for (basis, &y) in lagrange_basis(xs).iter().zip(ys.iter()) {
+        for (c, &b) in res.iter_mut().zip(basis.iter()) {
+            *c += b * y;
+        }
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2_field::types::Sample;
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn test_poly_mul_add() {
+        let mut rng = thread_rng();
+
+        let a = (0..4).map(|_| GFp5::sample(&mut rng)).collect::<Vec<_>>();
+        let b = (0..7).map(|_| GFp5::sample(&mut rng)).collect::<Vec<_>>();
+        let x = GFp5::sample(&mut rng);
+
+        assert_eq!(poly_eval(&poly_mul(&a, &b), x), poly_eval(&a, x) * poly_eval(&b, x));
+        assert_eq!(poly_eval(&poly_add(&a, &b), x), poly_eval(&a, x) + poly_eval(&b, x));
+    }
+
+    #[test]
+    fn test_poly_interpolate() {
+        let mut rng = thread_rng();
+
+        let coeffs = (0..5).map(|_| GFp5::sample(&mut rng)).collect::<Vec<_>>();
+        let xs = (1..=5).map(GFp5::from_canonical_u64).collect::<Vec<_>>();
+        let ys = xs.iter().map(|&x| poly_eval(&coeffs, x)).collect::<Vec<_>>();
+
+        assert_eq!(poly_interpolate(&xs, &ys), coeffs);
+
+        let at = GFp5::sample(&mut rng);
+        let interpolated_at = lagrange_coefficients(&xs, at)
+            .iter()
+            .zip(ys.iter())
+            .fold(GFp5::ZERO, |acc, (&l, &y)| acc + l * y);
+        assert_eq!(interpolated_at, poly_eval(&coeffs, at));
+    }
+}
@@ -0,0 +1,189 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use plonky2::hash::hashing::hash_n_to_m_no_pad;
+use plonky2::hash::poseidon::PoseidonPermutation;
+use plonky2_field::types::{Field, Sample};
+use rand::RngCore;
+
+use super::curve::Point;
+use super::hash_to_curve::hash_to_curve;
+use super::scalar_field::{hash_to_scalar, hash_to_scalar_prefix, Scalar};
+use super::GFp;
+
+// Linkable ring signatures (LSAG, https://eprint.iacr.org/2004/027), as in CryptoNote/Monero.
+// The signer owns one key pair (x, P = x * G) of a ring P_0, ..., P_{n-1}, and publishes
+// the key image I = x * H(P), where H hashes to the curve. Two signatures with the same key
+// image were made with the same key, whatever the rings and messages.
+//
+// Starting from c_{j+1} = hash(ring, I, m, a * G, a * H(P_j)) at the signer index j, the
+// signer walks around the ring with random responses s_i:
+//   L_i = s_i * G + c_i * P_i, R_i = s_i * H(P_i) + c_i * I, c_{i+1} = hash(ring, I, m, L_i, R_i)
+// and closes it with s_j = a - c_j * x. The verifier walks around the whole ring from c_0
+// and checks that it gets back to c_0.
+//
+// The ring enters the challenges through a digest, so that the cost of verifying is linear
+// in the size of the ring.
+
+pub(crate) const RING_DIGEST_DOMAIN: &[u8] = b"ecgfp5-lsag-ring";
+pub(crate) const KEY_IMAGE_DOMAIN: &[u8] = b"ecgfp5-lsag-key-image";
+pub(crate) const RING_CHALLENGE_DOMAIN: &[u8] = b"ecgfp5-lsag-challenge";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RingSignature {
+    pub key_image: Point,
+    pub c0: Scalar,
+    pub responses: Vec<Scalar>,
+}
+
+/// Returns H(pk), the point that the secret key is multiplied with to get the key image.
+pub fn key_image_base(pk: Point) -> Point {
+    hash_to_curve(KEY_IMAGE_DOMAIN, &pk.encode().0)
+}
+
+pub fn key_image(sk: Scalar) -> Point {
+    key_image_base(Point::GENERATOR * sk) * sk
+}
+
+/// Signs `message` on behalf of `ring`, where `ring[index]` is the public key of `sk`.
+pub fn ring_sign<R: RngCore + ?Sized>(
+    ring: &[Point],
+    index: usize,
+    sk: Scalar,
+    message: &[GFp],
+    rng: &mut R,
+) -> RingSignature {
+    assert!(ring[index].equals(Point::GENERATOR * sk), "the secret key isn't in the ring");
+
+    let n = ring.len();
+    let digest = ring_digest(ring);
+    let key_image = key_image_base(ring[index]) * sk;
+
+    let a = Scalar::sample(rng);
+    let mut challenges = vec![Scalar::ZERO; n];
+    let mut responses = vec![Scalar::ZERO; n];
+    challenges[(index + 1) % n] = ring_challenge(
+        &digest,
+        key_image,
+        message,
+        Point::GENERATOR * a,
+        key_image_base(ring[index]) * a,
+    );
+    for k in 1..n {
+        let i = (index + k) % n;
+        responses[i] = Scalar::sample(rng);
+        let (l, r) = ring_commitments(ring[i], key_image, challenges[i], responses[i]);
+        challenges[(i + 1) % n] = ring_challenge(&digest, key_image, message, l, r);
+    }
+    responses[index] = a - challenges[index] * sk;
+
+    RingSignature {
+        key_image,
+        c0: challenges[0],
+        responses,
+    }
+}
+
+pub fn ring_verify(ring: &[Point], message: &[GFp], signature: &RingSignature) -> bool {
+    if ring.is_empty() || signature.responses.len() != ring.len() {
+        return false;
+    }
+
+    let digest = ring_digest(ring);
+    let c = ring
+        .iter()
+        .zip(signature.responses.iter())
+        .fold(signature.c0, |c, (&pk, &s)| {
+            let (l, r) = ring_commitments(pk, signature.key_image, c, s);
+            ring_challenge(&digest, signature.key_image, message, l, r)
+        });
+
+    c == signature.c0
+}
+
+/// Whether two valid signatures were made with the same secret key.
+pub fn ring_linked(a: &RingSignature, b: &RingSignature) -> bool {
+    a.key_image.equals(b.key_image)
+}
+
+pub(crate) fn ring_digest(ring: &[Point]) -> [GFp; 4] {
+    let inputs = ring.iter().flat_map(|p| p.encode().0).collect::<Vec<_>>();
+    let mut message = hash_to_scalar_prefix(RING_DIGEST_DOMAIN, inputs.len());
+    message.extend(inputs);
+    let outputs = hash_n_to_m_no_pad::<GFp, PoseidonPermutation>(&message, 4);
+    [outputs[0], outputs[1], outputs[2], outputs[3]]
+}
+
+// L = s * G + c * P, R = s * H(P) + c * I
+fn ring_commitments(pk: Point, key_image: Point, c: Scalar, s: Scalar) -> (Point, Point) {
+    (
+        Point::GENERATOR * s + pk * c,
+        key_image_base(pk) * s + key_image * c,
+    )
+}
+
+fn ring_challenge(digest: &[GFp; 4], key_image: Point, message: &[GFp], l: Point, r: Point) -> Scalar {
+    let mut inputs = digest.to_vec();
+    inputs.extend(key_image.encode().0);
+    inputs.extend_from_slice(message);
+    inputs.extend(l.encode().0);
+    inputs.extend(r.encode().0);
+    hash_to_scalar(RING_CHALLENGE_DOMAIN, &inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn test_ring_signature() {
+        let mut rng = thread_rng();
+
+        let sks = (0..4).map(|_| Scalar::sample(&mut rng)).collect::<Vec<_>>();
+        let ring = sks.iter().map(|&sk| Point::GENERATOR * sk).collect::<Vec<_>>();
+        let message = [GFp::sample(&mut rng), GFp::sample(&mut rng)];
+
+        for (index, &sk) in sks.iter().enumerate() {
+            let sig = ring_sign(&ring, index, sk, &message, &mut rng);
+            assert!(ring_verify(&ring, &message, &sig));
+            assert!(sig.key_image.equals(key_image(sk)));
+        }
+
+        let sig = ring_sign(&ring, 1, sks[1], &message, &mut rng);
+
+        // wrong message, ring or signature
+        assert!(!ring_verify(&ring, &message[..1], &sig));
+        assert!(!ring_verify(&ring[..3], &message, &sig));
+        let mut other_ring = ring.clone();
+        other_ring[3] = Point::sample(&mut rng);
+        assert!(!ring_verify(&other_ring, &message, &sig));
+        let mut bad_sig = sig.clone();
+        bad_sig.responses[2] += Scalar::ONE;
+        assert!(!ring_verify(&ring, &message, &bad_sig));
+        let bad_sig = RingSignature { key_image: key_image(sks[0]), ..sig.clone() };
+        assert!(!ring_verify(&ring, &message, &bad_sig));
+
+        // a single-member ring is a plain signature
+        let sig = ring_sign(&ring[..1], 0, sks[0], &message, &mut rng);
+        assert!(ring_verify(&ring[..1], &message, &sig));
+    }
+
+    #[test]
+    fn test_ring_linkability() {
+        let mut rng = thread_rng();
+
+        let sks = (0..3).map(|_| Scalar::sample(&mut rng)).collect::<Vec<_>>();
+        let ring = sks.iter().map(|&sk| Point::GENERATOR * sk).collect::<Vec<_>>();
+        let mut other_ring = ring.clone();
+        other_ring.swap(0, 2);
+        other_ring.push(Point::sample(&mut rng));
+
+        let sig1 = ring_sign(&ring, 2, sks[2], &[GFp::ONE], &mut rng);
+        let sig2 = ring_sign(&other_ring, 0, sks[2], &[GFp::TWO], &mut rng);
+        let sig3 = ring_sign(&ring, 1, sks[1], &[GFp::ONE], &mut rng);
+
+        assert!(ring_linked(&sig1, &sig2));
+        assert!(!ring_linked(&sig1, &sig3));
+    }
+}
@@ -12,12 +12,13 @@ use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAss
 
 use itertools::Itertools;
 use num::bigint::BigUint;
-use num::One;
 use serde::{Deserialize, Serialize};
 
-use plonky2_field::types::{Field, PrimeField, Sample, PrimeField64};
+use plonky2::hash::hashing::hash_n_to_m_no_pad;
+use plonky2::hash::poseidon::PoseidonPermutation;
+use plonky2_field::types::{Field, Field64, PrimeField, Sample, PrimeField64};
 
-use super::GFp5;
+use super::{GFp, GFp5};
 
 /// The Scalar field of the ECgFP5 elliptic curve.
 ///
@@ -191,8 +192,7 @@ impl Field for Scalar {
             return None;
         }
 
-        // Fermat's Little Theorem
-        Some(self.exp_biguint(&(Self::order() - BigUint::one() - BigUint::one())))
+        Some(self.invert())
     }
 
     fn from_noncanonical_biguint(val: BigUint) -> Self {
@@ -303,6 +303,15 @@ impl Scalar {
         0x53CACA12110CA256,
     ]);
 
+    // (q - 1) / 2, where n - 1 = 2^5 * q with q odd. Used for square roots.
+    const Q_MINUS_ONE_HALF: [u64; 5] = [
+        0x73A03F665A522FFF,
+        0xE7A22170E75C9282,
+        0x59FFFFFF9B3EE018,
+        0x1DFFFFFFC4000000,
+        0x01FFFFFFF6000000,
+    ];
+
     // raw addition (no reduction)
     fn add_inner(self, a: Self) -> Self {
         let mut r = Self::ZERO;
@@ -363,6 +372,23 @@ impl Scalar {
         Self::ZERO.sub(self)
     }
 
+    // raw halving (no reduction), rounding down.
+    fn half_inner(self) -> Self {
+        let mut r = Self::ZERO;
+        for i in 0..4 {
+            r.0[i] = (self.0[i] >> 1) | (self.0[i + 1] << 63);
+        }
+        r.0[4] = self.0[4] >> 1;
+        r
+    }
+
+    // Scalar halving.
+    fn half(self) -> Self {
+        // if self is odd, then self + n is even, and it fits on 320 bits.
+        let odd = (self.0[0] & 1).wrapping_neg();
+        Self::select(odd, self, self.add_inner(Self::N)).half_inner()
+    }
+
     // Montgomery multiplication.
     // Returns (self*rhs)/2^320 mod n.
     // 'self' MUST be less than n (the other operand can be up to 2^320-1).
@@ -456,6 +482,10 @@ impl Scalar {
         (r, c)
     }
 
+    /// Reduce the canonical 320-bit integer encoding of `x` modulo n.
+    /// Even if `x` is uniform, the statistical distance between the
+    /// output and a uniform scalar is about 2^-29.7, which is too much for
+    /// nonces or challenges: use `hash_to_scalar` for those.
     pub fn from_gfp5(x: GFp5) -> Self {
         let QuinticExtension(limbs) = x;
         Self::from_noncanonical_biguint(
@@ -496,6 +526,15 @@ impl Scalar {
         acc
     }
 
+    /// Decode 80 uniformly random bytes into a scalar. The bytes are
+    /// interpreted as a 640-bit integer in little-endian unsigned
+    /// convention, which is then reduced modulo n. Since n < 2^319, the
+    /// statistical distance between the output and a uniform scalar is
+    /// lower than n/2^640 < 2^-321. This is constant-time.
+    pub fn from_uniform_bytes(buf: &[u8; 80]) -> Self {
+        Self::from_noncanonical_bytes(buf)
+    }
+
     /// Encode this scalar over exactly 40 bytes.
     pub fn encode(self) -> [u8; 40] {
         let mut r = [0u8; 40];
@@ -617,6 +656,120 @@ impl Scalar {
         }
     }
 
+    /// Invert this scalar. If this scalar is zero, then zero is returned.
+    /// This is constant-time: it uses the binary GCD of Möller (the one
+    /// behind GMP's mpn_sec_invert), with a fixed number of iterations.
+    pub fn invert(self) -> Self {
+        // Invariants: a = u*self mod n, b = v*self mod n, and b is odd.
+        // Each iteration decreases len(a) + len(b) by at least 1 until
+        // a == 0, at which point b == gcd(self, n). Since n has length
+        // 319, 2*319 iterations are enough. If self is zero, then v
+        // stays zero.
+        let mut a = self;
+        let mut b = Self::N;
+        let mut u = Self::ONE;
+        let mut v = Self::ZERO;
+        for _ in 0..(2 * 319) {
+            // If a is odd, replace it with a - b, after swapping a and
+            // b if a < b. Then a is even and we can halve it.
+            let a_odd = (a.0[0] & 1).wrapping_neg();
+            let (d, borrow) = a.sub_inner(b);
+            let swap = a_odd & borrow;
+            let d = Self::select(swap, d, Self::ZERO.sub_inner(d).0);
+            let w = Self::select(swap, u.sub(v), v.sub(u));
+            b = Self::select(swap, b, a);
+            v = Self::select(swap, v, u);
+            a = Self::select(a_odd, a, d).half_inner();
+            u = Self::select(a_odd, u, w).half();
+        }
+        v
+    }
+
+    /// Invert all scalars in `xs` with a single inversion (Montgomery's
+    /// trick), writing the results into `out`, which must have the same
+    /// length. Zero scalars are "inverted" to zero. This does not
+    /// allocate, and is constant-time.
+    pub fn batch_invert(xs: &[Self], out: &mut [Self]) {
+        assert_eq!(xs.len(), out.len());
+
+        // out[i] = product of the non-zero xs[j] for j < i
+        let mut acc = Self::ONE;
+        for (&x, o) in xs.iter().zip(out.iter_mut()) {
+            *o = acc;
+            acc = Self::select(x.iszero(), acc.mul(x), acc);
+        }
+
+        let mut acc_inv = acc.invert();
+        for (&x, o) in xs.iter().zip(out.iter_mut()).rev() {
+            let zx = x.iszero();
+            let inv = o.mul(acc_inv);
+            acc_inv = Self::select(zx, acc_inv.mul(x), acc_inv);
+            *o = Self::select(zx, inv, Self::ZERO);
+        }
+    }
+
+    /// Raise this scalar to the power `e`, provided as 64-bit limbs in
+    /// little-endian order. This is constant-time (only the number of
+    /// limbs of the exponent may leak).
+    pub fn pow(self, e: &[u64]) -> Self {
+        // window: self^i for i in 0..16
+        let mut win = [Self::ONE; 16];
+        for i in 1..16 {
+            win[i] = win[i - 1].mul(self);
+        }
+
+        let mut r = Self::ONE;
+        for &limb in e.iter().rev() {
+            for j in (0..16).rev() {
+                for _ in 0..4 {
+                    r = r.mul(r);
+                }
+
+                let k = (limb >> (4 * j)) & 0xF;
+                let mut t = Self::ONE;
+                for (i, &w) in win.iter().enumerate() {
+                    let eq = (((i as u64) ^ k).wrapping_sub(1) >> 63).wrapping_neg();
+                    t = Self::select(eq, t, w);
+                }
+                r = r.mul(t);
+            }
+        }
+        r
+    }
+
+    /// Compute a square root of this scalar. If this scalar is not a
+    /// quadratic residue, then `None` is returned. Which of the two
+    /// roots is returned is unspecified. This uses a constant-time
+    /// variant of Tonelli-Shanks, with n - 1 = 2^5 * q.
+    pub fn sqrt(self) -> Option<Self> {
+        // r = self^((q + 1) / 2) and t = self^q
+        let z = self.pow(&Self::Q_MINUS_ONE_HALF);
+        let mut r = z.mul(self);
+        let mut t = r.mul(z);
+
+        // Invariants: r^2 = self * t and c has order 2^(k+1). If self is
+        // a square, then t^(2^k) = 1, so t^(2^(k-1)) is either 1 or -1.
+        // In the latter case, we multiply t by c^2 (and r by c).
+        let mut c = Self::POWER_OF_TWO_GENERATOR;
+        for k in (1..Self::TWO_ADICITY).rev() {
+            let mut tt = t;
+            for _ in 1..k {
+                tt = tt.mul(tt);
+            }
+            let fix = !tt.equals(Self::ONE);
+            let c2 = c.mul(c);
+            r = Self::select(fix, r, r.mul(c));
+            t = Self::select(fix, t, t.mul(c2));
+            c = c2;
+        }
+
+        if r.mul(r).equals(self) == 0 {
+            None
+        } else {
+            Some(r)
+        }
+    }
+
     /// Compare this scalar with zero. Returned value is 0xFFFFFFFFFFFFFFFF
     /// if this scalar is zero, or 0 otherwise.
     pub fn iszero(self) -> u64 {
@@ -637,6 +790,62 @@ impl Scalar {
     }
 }
 
+/// Number of Poseidon output elements squeezed by `hash_to_scalar`.
+pub(crate) const HASH_TO_SCALAR_NUM_OUTPUTS: usize = 10;
+
+/// Returns the elements that `hash_to_scalar` absorbs before its inputs:
+/// the byte length of `domain`, then `domain` packed by chunks of 7 bytes
+/// (little-endian), then the number of inputs. This makes the encoding of
+/// (domain, inputs) injective.
+pub(crate) fn hash_to_scalar_prefix(domain: &[u8], num_inputs: usize) -> Vec<GFp> {
+    let mut prefix = Vec::with_capacity(2 + domain.len().div_ceil(7));
+    prefix.push(GFp::from_canonical_usize(domain.len()));
+    for chunk in domain.chunks(7) {
+        let mut buf = [0u8; 8];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        prefix.push(GFp::from_canonical_u64(u64::from_le_bytes(buf)));
+    }
+    prefix.push(GFp::from_canonical_usize(num_inputs));
+    prefix
+}
+
+/// Squeezes `num_outputs` field elements from Poseidon, after absorbing `hash_to_scalar_prefix(domain, inputs.len())`
+/// and then `inputs`. This is the framing of `hash_to_scalar`, shared by the other domain-separated hashes
+/// of the crate; the in-circuit counterpart is `gadgets::scalar_field::hash_with_prefix`.
+pub(crate) fn hash_with_prefix(domain: &[u8], inputs: &[GFp], num_outputs: usize) -> Vec<GFp> {
+    let mut message = hash_to_scalar_prefix(domain, inputs.len());
+    message.extend_from_slice(inputs);
+    hash_n_to_m_no_pad::<GFp, PoseidonPermutation>(&message, num_outputs)
+}
+
+// Packs bytes into field elements, 4 bytes (little-endian) per element;
+// the last chunk is padded with zeros.
+pub(crate) fn bytes_to_u32_elements(bytes: &[u8]) -> Vec<GFp> {
+    bytes
+        .chunks(4)
+        .map(|chunk| {
+            let mut buf = [0u8; 4];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            GFp::from_canonical_u32(u32::from_le_bytes(buf))
+        })
+        .collect()
+}
+
+/// Hash `inputs` to a scalar with Poseidon, under the domain separation
+/// tag `domain`. Ten field elements x_i are squeezed and interpreted as
+/// the integer sum(x_i * p^i), which is uniform in [0, p^10) and is then
+/// reduced modulo n; since p^10 > 2^639, the bias is lower than 2^-320.
+/// The `hash_to_scalar` gadget computes the same function.
+pub fn hash_to_scalar(domain: &[u8], inputs: &[GFp]) -> Scalar {
+    let outputs = hash_with_prefix(domain, inputs, HASH_TO_SCALAR_NUM_OUTPUTS);
+
+    let p = Scalar::from_canonical_u64(GFp::ORDER);
+    outputs
+        .iter()
+        .rev()
+        .fold(Scalar::ZERO, |acc, x| acc * p + Scalar::from_canonical_u64(x.to_canonical_u64()))
+}
+
 /// A custom 161-bit integer type; used for splitting a scalar into a
 /// fraction. Negative values use two's complement notation; the value
 /// is truncated to 161 bits (upper bits in the top limb are ignored).
@@ -967,7 +1176,11 @@ impl Signed640 {
 mod tests {
     use crate::test_field_arithmetic;
 
-    use super::Scalar;
+    use num::BigUint;
+    use plonky2_field::types::{Field, PrimeField, Sample};
+
+    use super::{biguint_from_array, hash_to_scalar, Scalar};
+    use crate::curve::GFp;
     use rand::{thread_rng, Rng};
 
     #[test]
@@ -1086,5 +1299,103 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_invert() {
+        assert!(Scalar::ZERO.invert().iszero() == 0xFFFFFFFFFFFFFFFF);
+        assert!(Scalar::ONE.invert().equals(Scalar::ONE) == 0xFFFFFFFFFFFFFFFF);
+        assert!(Scalar::NEG_ONE.invert().equals(Scalar::NEG_ONE) == 0xFFFFFFFFFFFFFFFF);
+
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let mut sbuf = [0u8; 48];
+            rng.fill(&mut sbuf);
+            let s = Scalar::from_noncanonical_bytes(&sbuf);
+            let s_inv = s.invert();
+            assert!((s * s_inv).equals(Scalar::ONE) == 0xFFFFFFFFFFFFFFFF);
+            assert!(s_inv == s.exp_biguint(&(Scalar::order() - 2u32)));
+        }
+    }
+
+    #[test]
+    fn test_batch_invert() {
+        let mut rng = thread_rng();
+        let mut xs = [Scalar::ZERO; 9];
+        for (i, x) in xs.iter_mut().enumerate() {
+            // leave some zeros in, including the first and last entries
+            if i % 4 != 0 {
+                *x = Scalar::sample(&mut rng);
+            }
+        }
+
+        let mut out = [Scalar::ZERO; 9];
+        Scalar::batch_invert(&xs, &mut out);
+        for (&x, &x_inv) in xs.iter().zip(out.iter()) {
+            assert!(x_inv == x.invert());
+        }
+
+        Scalar::batch_invert(&[], &mut []);
+    }
+
+    #[test]
+    fn test_sqrt() {
+        let q = (Scalar::order() - 1u32) >> Scalar::TWO_ADICITY;
+        assert!(biguint_from_array(Scalar::Q_MINUS_ONE_HALF) == (q - 1u32) >> 1);
+
+        assert!(Scalar::ZERO.sqrt() == Some(Scalar::ZERO));
+        // the multiplicative generator is not a square
+        assert!(Scalar::MULTIPLICATIVE_GROUP_GENERATOR.sqrt().is_none());
+
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let x = Scalar::sample(&mut rng);
+            let square = x * x;
+            let root = square.sqrt().unwrap();
+            assert!(root == x || root == -x);
+
+            let non_square = square * Scalar::MULTIPLICATIVE_GROUP_GENERATOR;
+            assert!(non_square.sqrt().is_none());
+        }
+    }
+
+    #[test]
+    fn test_pow() {
+        let mut rng = thread_rng();
+        for _ in 0..10 {
+            let x = Scalar::sample(&mut rng);
+            let e: [u64; 3] = rng.gen();
+            let e_biguint = BigUint::from_slice(&[
+                e[0] as u32, (e[0] >> 32) as u32,
+                e[1] as u32, (e[1] >> 32) as u32,
+                e[2] as u32, (e[2] >> 32) as u32,
+            ]);
+            assert!(x.pow(&e) == x.exp_biguint(&e_biguint));
+        }
+        assert!(Scalar::TWO.pow(&[]) == Scalar::ONE);
+    }
+
+    #[test]
+    fn test_from_uniform_bytes() {
+        let mut rng = thread_rng();
+        for _ in 0..30 {
+            let mut buf = [0u8; 80];
+            rng.fill(&mut buf[..]);
+            let s = Scalar::from_uniform_bytes(&buf);
+            assert!(s.to_canonical_biguint() == BigUint::from_bytes_le(&buf) % Scalar::order());
+        }
+    }
+
+    #[test]
+    fn test_hash_to_scalar() {
+        let inputs = [GFp::ONE, GFp::TWO, GFp::NEG_ONE];
+        let s = hash_to_scalar(b"test", &inputs);
+
+        assert!(s == hash_to_scalar(b"test", &inputs));
+        assert!(s != hash_to_scalar(b"test2", &inputs));
+        // trailing zeros and empty domains are not ambiguous
+        assert!(hash_to_scalar(b"", &[]) != hash_to_scalar(b"", &[GFp::ZERO]));
+        assert!(hash_to_scalar(b"", &[GFp::ZERO]) != hash_to_scalar(b"\0", &[]));
+        assert!(s.to_canonical_biguint() < Scalar::order());
+    }
+
     test_field_arithmetic!(crate::curve::scalar_field::Scalar);
 }
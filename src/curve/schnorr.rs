@@ -0,0 +1,63 @@
+use super::curve::Point;
+use super::nonce::derive_nonce;
+use super::scalar_field::{hash_to_scalar, Scalar};
+use super::GFp;
+
+// Schnorr signatures with key prefixing:
+//   R = k * G, e = hash(R, pk, m), s = k - e * sk
+// checked by recomputing R = s * G + e * pk. The `curve_verify_schnorr` gadget checks the same equation.
+
+pub(crate) const SCHNORR_CHALLENGE_DOMAIN: &[u8] = b"ecgfp5-schnorr-challenge";
+const SCHNORR_NONCE_DOMAIN: &[u8] = b"ecgfp5-schnorr-nonce";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchnorrSignature {
+    pub s: Scalar,
+    pub e: Scalar,
+}
+
+/// Signs `message` with `sk`. The nonce is derived deterministically with `derive_nonce`.
+pub fn schnorr_sign(sk: Scalar, message: &[GFp]) -> SchnorrSignature {
+    let pk = Point::GENERATOR * sk;
+    let k = derive_nonce(SCHNORR_NONCE_DOMAIN, sk, message, None);
+    let e = schnorr_challenge(Point::GENERATOR * k, pk, message);
+
+    SchnorrSignature { s: k - e * sk, e }
+}
+
+pub fn schnorr_verify(pk: Point, message: &[GFp], signature: &SchnorrSignature) -> bool {
+    let r = Point::GENERATOR * signature.s + pk * signature.e;
+    schnorr_challenge(r, pk, message) == signature.e
+}
+
+pub(crate) fn schnorr_challenge(r: Point, pk: Point, message: &[GFp]) -> Scalar {
+    let mut inputs = r.encode().0.to_vec();
+    inputs.extend(pk.encode().0);
+    inputs.extend_from_slice(message);
+    hash_to_scalar(SCHNORR_CHALLENGE_DOMAIN, &inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2_field::types::{Field, Sample};
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn test_schnorr() {
+        let mut rng = thread_rng();
+
+        let sk = Scalar::sample(&mut rng);
+        let pk = Point::GENERATOR * sk;
+        let message = [GFp::sample(&mut rng), GFp::sample(&mut rng)];
+
+        let sig = schnorr_sign(sk, &message);
+        assert!(schnorr_verify(pk, &message, &sig));
+        assert_eq!(sig, schnorr_sign(sk, &message));
+
+        assert!(!schnorr_verify(pk, &message[..1], &sig));
+        assert!(!schnorr_verify(Point::sample(&mut rng), &message, &sig));
+        assert!(!schnorr_verify(pk, &message, &SchnorrSignature { s: sig.s + Scalar::ONE, e: sig.e }));
+    }
+}
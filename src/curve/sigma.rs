@@ -0,0 +1,298 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use plonky2_field::types::{Field, Sample};
+use rand::RngCore;
+
+use super::curve::Point;
+use super::scalar_field::{hash_to_scalar, Scalar};
+use super::GFp;
+
+// Sigma protocols for linear relations, made non-interactive with Fiat-Shamir.
+//
+// A linear relation is a set of equations Y_j = sum_k x_{i_k} * P_k over secret scalars x_i.
+// The prover commits to T_j = sum_k r_{i_k} * P_k for random r_i, gets the challenge
+// c = hash_to_scalar(domain, statement || T) and responds with s_i = r_i + c * x_i.
+// The verifier recomputes T_j = sum_k s_{i_k} * P_k - c * Y_j and checks the challenge.
+//
+// The transcript binds the shape of the relations (which secret multiplies which base) as well
+// as their points, so the domain separation tag only needs to identify the protocol.
+
+/// One equation of a linear relation: `image = sum(secrets[i] * base for (i, base) in terms)`.
+#[derive(Clone, Debug)]
+pub struct LinearEquation {
+    pub terms: Vec<(usize, Point)>,
+    pub image: Point,
+}
+
+/// A statement "I know secrets such that every equation holds".
+#[derive(Clone, Debug)]
+pub struct LinearRelation {
+    pub num_secrets: usize,
+    pub equations: Vec<LinearEquation>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearProof {
+    pub c: Scalar,
+    pub responses: Vec<Scalar>,
+}
+
+/// A proof of knowledge of the secrets of one relation among several (OR-composition),
+/// without revealing which. The challenges sum up to the Fiat-Shamir challenge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrProof {
+    pub challenges: Vec<Scalar>,
+    pub responses: Vec<Vec<Scalar>>,
+}
+
+impl LinearRelation {
+    /// Knowledge of x such that y = x * g.
+    pub fn schnorr(g: Point, y: Point) -> Self {
+        Self {
+            num_secrets: 1,
+            equations: vec![LinearEquation { terms: vec![(0, g)], image: y }],
+        }
+    }
+
+    /// Knowledge of x such that y = x * g and z = x * h (Chaum-Pedersen).
+    pub fn dleq(g: Point, y: Point, h: Point, z: Point) -> Self {
+        Self {
+            num_secrets: 1,
+            equations: vec![
+                LinearEquation { terms: vec![(0, g)], image: y },
+                LinearEquation { terms: vec![(0, h)], image: z },
+            ],
+        }
+    }
+
+    /// Knowledge of an opening (x, r) of the Pedersen commitment y = x * g + r * h.
+    pub fn pedersen(g: Point, h: Point, y: Point) -> Self {
+        Self {
+            num_secrets: 2,
+            equations: vec![LinearEquation { terms: vec![(0, g), (1, h)], image: y }],
+        }
+    }
+
+    /// Checks that `secrets` satisfy the relation.
+    pub fn is_satisfied(&self, secrets: &[Scalar]) -> bool {
+        secrets.len() == self.num_secrets
+            && self.equations.iter().all(|eq| eval_terms(&eq.terms, secrets).equals(eq.image))
+    }
+
+    pub fn prove<R: RngCore + ?Sized>(&self, domain: &[u8], secrets: &[Scalar], rng: &mut R) -> LinearProof {
+        assert!(self.is_satisfied(secrets), "secrets don't satisfy the relation");
+
+        let nonces = (0..self.num_secrets).map(|_| Scalar::sample(rng)).collect::<Vec<_>>();
+        let commitments = self.commitments(&nonces);
+        let c = challenge(domain, &[self], &[commitments]);
+        let responses = nonces.iter().zip(secrets.iter()).map(|(&r, &x)| r + c * x).collect();
+
+        LinearProof { c, responses }
+    }
+
+    pub fn verify(&self, domain: &[u8], proof: &LinearProof) -> bool {
+        if proof.responses.len() != self.num_secrets {
+            return false;
+        }
+
+        let commitments = self.recompute_commitments(proof.c, &proof.responses);
+        challenge(domain, &[self], &[commitments]) == proof.c
+    }
+
+    /// Proves knowledge of the secrets of `relations[index]`, without revealing `index`.
+    pub fn prove_or<R: RngCore + ?Sized>(
+        relations: &[LinearRelation],
+        domain: &[u8],
+        index: usize,
+        secrets: &[Scalar],
+        rng: &mut R,
+    ) -> OrProof {
+        assert!(relations[index].is_satisfied(secrets), "secrets don't satisfy the relation");
+
+        // simulate the other branches with random challenges and responses
+        let mut challenges = Vec::with_capacity(relations.len());
+        let mut responses = Vec::with_capacity(relations.len());
+        let mut commitments = Vec::with_capacity(relations.len());
+        let mut nonces = Vec::new();
+        for (i, relation) in relations.iter().enumerate() {
+            if i == index {
+                nonces = (0..relation.num_secrets).map(|_| Scalar::sample(rng)).collect();
+                challenges.push(Scalar::ZERO);
+                responses.push(Vec::new());
+                commitments.push(relation.commitments(&nonces));
+            } else {
+                let c = Scalar::sample(rng);
+                let s = (0..relation.num_secrets).map(|_| Scalar::sample(rng)).collect::<Vec<_>>();
+                commitments.push(relation.recompute_commitments(c, &s));
+                challenges.push(c);
+                responses.push(s);
+            }
+        }
+
+        let relation_refs = relations.iter().collect::<Vec<_>>();
+        let c = challenge(domain, &relation_refs, &commitments);
+        challenges[index] = c - challenges.iter().copied().sum::<Scalar>();
+        responses[index] = nonces.iter().zip(secrets.iter()).map(|(&r, &x)| r + challenges[index] * x).collect();
+
+        OrProof { challenges, responses }
+    }
+
+    pub fn verify_or(relations: &[LinearRelation], domain: &[u8], proof: &OrProof) -> bool {
+        if proof.challenges.len() != relations.len() || proof.responses.len() != relations.len() {
+            return false;
+        }
+        if relations.iter().zip(proof.responses.iter()).any(|(relation, s)| s.len() != relation.num_secrets) {
+            return false;
+        }
+
+        let commitments = relations
+            .iter()
+            .zip(proof.challenges.iter().zip(proof.responses.iter()))
+            .map(|(relation, (&c, s))| relation.recompute_commitments(c, s))
+            .collect::<Vec<_>>();
+
+        let relation_refs = relations.iter().collect::<Vec<_>>();
+        challenge(domain, &relation_refs, &commitments) == proof.challenges.iter().copied().sum()
+    }
+
+    fn commitments(&self, nonces: &[Scalar]) -> Vec<Point> {
+        self.equations.iter().map(|eq| eval_terms(&eq.terms, nonces)).collect()
+    }
+
+    fn recompute_commitments(&self, c: Scalar, responses: &[Scalar]) -> Vec<Point> {
+        self.equations
+            .iter()
+            .map(|eq| eval_terms(&eq.terms, responses) - eq.image * c)
+            .collect()
+    }
+}
+
+fn eval_terms(terms: &[(usize, Point)], scalars: &[Scalar]) -> Point {
+    terms
+        .iter()
+        .fold(Point::NEUTRAL, |acc, &(i, base)| acc + base * scalars[i])
+}
+
+// The transcript is the number of relations, then the shape and the encoding of every base and image of each
+// relation, followed by the commitments.
+pub(crate) fn challenge(domain: &[u8], relations: &[&LinearRelation], commitments: &[Vec<Point>]) -> Scalar {
+    let mut inputs = vec![GFp::from_canonical_usize(relations.len())];
+    for relation in relations {
+        let term_indices = relation
+            .equations
+            .iter()
+            .map(|eq| eq.terms.iter().map(|&(i, _)| i).collect())
+            .collect::<Vec<_>>();
+        inputs.extend(relation_shape(relation.num_secrets, &term_indices));
+
+        for eq in relation.equations.iter() {
+            for &(_, base) in eq.terms.iter() {
+                inputs.extend(base.encode().0);
+            }
+            inputs.extend(eq.image.encode().0);
+        }
+    }
+    for &t in commitments.iter().flatten() {
+        inputs.extend(t.encode().0);
+    }
+
+    hash_to_scalar(domain, &inputs)
+}
+
+// The shape of a relation: its number of secrets and equations, then for each equation its number of terms
+// and the index of the secret of each term. `term_indices[j]` holds the indices of the j-th equation.
+pub(crate) fn relation_shape(num_secrets: usize, term_indices: &[Vec<usize>]) -> Vec<GFp> {
+    let mut shape = vec![num_secrets, term_indices.len()];
+    for indices in term_indices {
+        shape.push(indices.len());
+        shape.extend_from_slice(indices);
+    }
+    shape.into_iter().map(GFp::from_canonical_usize).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn test_schnorr_and_dleq() {
+        let mut rng = thread_rng();
+
+        let x = Scalar::sample(&mut rng);
+        let h = Point::sample(&mut rng);
+        let y = Point::GENERATOR * x;
+        let z = h * x;
+
+        let schnorr = LinearRelation::schnorr(Point::GENERATOR, y);
+        let proof = schnorr.prove(b"schnorr", &[x], &mut rng);
+        assert!(schnorr.verify(b"schnorr", &proof));
+        assert!(!schnorr.verify(b"other", &proof));
+        assert!(!LinearRelation::schnorr(Point::GENERATOR, z).verify(b"schnorr", &proof));
+
+        let dleq = LinearRelation::dleq(Point::GENERATOR, y, h, z);
+        let proof = dleq.prove(b"dleq", &[x], &mut rng);
+        assert!(dleq.verify(b"dleq", &proof));
+        assert!(!LinearRelation::dleq(Point::GENERATOR, y, h, z + h).verify(b"dleq", &proof));
+        let bad_proof = LinearProof { c: proof.c, responses: vec![proof.responses[0] + Scalar::ONE] };
+        assert!(!dleq.verify(b"dleq", &bad_proof));
+    }
+
+    #[test]
+    fn test_pedersen_opening() {
+        let mut rng = thread_rng();
+
+        let (x, r) = (Scalar::sample(&mut rng), Scalar::sample(&mut rng));
+        let h = Point::sample(&mut rng);
+        let y = Point::GENERATOR * x + h * r;
+
+        let relation = LinearRelation::pedersen(Point::GENERATOR, h, y);
+        assert!(!relation.is_satisfied(&[r, x]));
+        let proof = relation.prove(b"pedersen", &[x, r], &mut rng);
+        assert!(relation.verify(b"pedersen", &proof));
+    }
+
+    #[test]
+    fn test_or_proof() {
+        let mut rng = thread_rng();
+
+        // e.g. a ring of public keys, we know one of the secret keys
+        let x = Scalar::sample(&mut rng);
+        let mut relations = (0..4)
+            .map(|_| LinearRelation::schnorr(Point::GENERATOR, Point::sample(&mut rng)))
+            .collect::<Vec<_>>();
+        relations[2] = LinearRelation::schnorr(Point::GENERATOR, Point::GENERATOR * x);
+
+        let proof = LinearRelation::prove_or(&relations, b"or", 2, &[x], &mut rng);
+        assert!(LinearRelation::verify_or(&relations, b"or", &proof));
+        assert!(!LinearRelation::verify_or(&relations[..3], b"or", &proof));
+
+        relations[2] = LinearRelation::schnorr(Point::GENERATOR, Point::sample(&mut rng));
+        assert!(!LinearRelation::verify_or(&relations, b"or", &proof));
+    }
+
+    #[test]
+    fn test_relation_shape_is_bound() {
+        let mut rng = thread_rng();
+
+        // x0 * G + x1 * H and x0 * G + x0 * H have the same points. With x0 = x1 and equal nonces, the
+        // commitments are the same under both, so only the shape in the transcript tells the relations apart
+        let x = Scalar::sample(&mut rng);
+        let h = Point::sample(&mut rng);
+        let y = (Point::GENERATOR + h) * x;
+        let relation = |indices: [usize; 2]| LinearRelation {
+            num_secrets: 2,
+            equations: vec![LinearEquation { terms: vec![(indices[0], Point::GENERATOR), (indices[1], h)], image: y }],
+        };
+        let (distinct, repeated) = (relation([0, 1]), relation([0, 0]));
+
+        let r = Scalar::sample(&mut rng);
+        let c = challenge(b"shape", &[&distinct], &[distinct.commitments(&[r, r])]);
+        let proof = LinearProof { c, responses: vec![r + c * x; 2] };
+
+        assert!(distinct.verify(b"shape", &proof));
+        assert!(repeated.recompute_commitments(c, &proof.responses)[0].equals(distinct.commitments(&[r, r])[0]));
+        assert!(!repeated.verify(b"shape", &proof));
+    }
+}
@@ -0,0 +1,121 @@
+use plonky2_field::types::Sample;
+use rand::RngCore;
+
+use super::curve::Point;
+use super::scalar_field::{hash_to_scalar, Scalar};
+
+// Dual-key stealth addresses. A recipient publishes a view key V = v*G and a spend key
+// S = s*G. To pay them, a sender picks a fresh ephemeral key r, publishes R = r*G, and
+// pays to the one-time key P = S + H(r*V)*G. Since r*V = v*R, the holder of the view key
+// can recognize the payment and the holder of the spend key can spend it with s + H(v*R),
+// while P alone can't be linked to (V, S).
+
+pub(crate) const STEALTH_DOMAIN: &[u8] = b"ecgfp5-stealth";
+
+/// The scalar H(shared), which is added to the spend key to get the one-time key.
+pub fn stealth_tweak(shared: Point) -> Scalar {
+    hash_to_scalar(STEALTH_DOMAIN, &shared.encode().0)
+}
+
+/// The secret keys of a recipient.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StealthSecretKey {
+    pub view_sk: Scalar,
+    pub spend_sk: Scalar,
+}
+
+/// What a recipient hands out to the wallet that scans for its payments:
+/// it can recognize them, but not spend them.
+#[derive(Clone, Copy, Debug)]
+pub struct StealthViewKey {
+    pub view_sk: Scalar,
+    pub spend_pk: Point,
+}
+
+/// The public address of a recipient.
+#[derive(Clone, Copy, Debug)]
+pub struct StealthAddress {
+    pub view_pk: Point,
+    pub spend_pk: Point,
+}
+
+impl StealthSecretKey {
+    pub fn sample<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        Self {
+            view_sk: Scalar::sample(rng),
+            spend_sk: Scalar::sample(rng),
+        }
+    }
+
+    pub fn address(&self) -> StealthAddress {
+        StealthAddress {
+            view_pk: Point::GENERATOR * self.view_sk,
+            spend_pk: Point::GENERATOR * self.spend_sk,
+        }
+    }
+
+    pub fn view_key(&self) -> StealthViewKey {
+        StealthViewKey {
+            view_sk: self.view_sk,
+            spend_pk: Point::GENERATOR * self.spend_sk,
+        }
+    }
+
+    /// Returns the secret key of the one-time key `one_time_pk`, if it was sent to us with `ephemeral_pk`.
+    pub fn one_time_secret_key(&self, ephemeral_pk: Point, one_time_pk: Point) -> Option<Scalar> {
+        self.view_key()
+            .scan(ephemeral_pk, one_time_pk)
+            .map(|tweak| self.spend_sk + tweak)
+    }
+}
+
+impl StealthViewKey {
+    /// Checks whether `one_time_pk` was sent to us with `ephemeral_pk`. If so, returns
+    /// the tweak to add to the spend secret key to get the one-time secret key.
+    pub fn scan(&self, ephemeral_pk: Point, one_time_pk: Point) -> Option<Scalar> {
+        let tweak = stealth_tweak(ephemeral_pk * self.view_sk);
+        (self.spend_pk + Point::GENERATOR * tweak).equals(one_time_pk).then_some(tweak)
+    }
+}
+
+impl StealthAddress {
+    /// Derives a one-time key for this address. `ephemeral_sk` must be freshly sampled for every
+    /// payment (including several outputs to the same address), otherwise the one-time keys repeat.
+    /// Returns the ephemeral public key, to be published along with the payment, and the one-time key.
+    pub fn derive_one_time_key(&self, ephemeral_sk: Scalar) -> (Point, Point) {
+        let ephemeral_pk = Point::GENERATOR * ephemeral_sk;
+        let tweak = stealth_tweak(self.view_pk * ephemeral_sk);
+        (ephemeral_pk, self.spend_pk + Point::GENERATOR * tweak)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn test_stealth_address() {
+        let mut rng = thread_rng();
+
+        let recipient = StealthSecretKey::sample(&mut rng);
+        let other = StealthSecretKey::sample(&mut rng);
+        let address = recipient.address();
+
+        let (ephemeral_pk, one_time_pk) = address.derive_one_time_key(Scalar::sample(&mut rng));
+
+        // the view key recognizes the payment, and the spend key can spend it
+        assert!(recipient.view_key().scan(ephemeral_pk, one_time_pk).is_some());
+        let one_time_sk = recipient.one_time_secret_key(ephemeral_pk, one_time_pk).unwrap();
+        assert!((Point::GENERATOR * one_time_sk).equals(one_time_pk));
+
+        // others don't
+        assert!(other.view_key().scan(ephemeral_pk, one_time_pk).is_none());
+
+        // fresh ephemeral keys give unlinkable one-time keys
+        let (_, one_time_pk_2) = address.derive_one_time_key(Scalar::sample(&mut rng));
+        assert!(!one_time_pk.equals(one_time_pk_2));
+        assert!(!one_time_pk.equals(address.spend_pk));
+    }
+}
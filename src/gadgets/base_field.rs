@@ -1,4 +1,5 @@
 use alloc::vec::Vec;
+use num::{BigUint, Zero};
 use plonky2_ecdsa::gadgets::nonnative::NonNativeTarget;
 use plonky2_field::extension::quintic::QuinticExtension;
 use plonky2_field::goldilocks_field::GoldilocksField;
@@ -106,6 +107,15 @@ pub trait CircuitBuilderGFp5<F: RichField + Extendable<5>> {
         b: QuinticExtensionTarget,
     ) -> QuinticExtensionTarget;
     fn inverse_quintic_ext(&mut self, x: QuinticExtensionTarget) -> QuinticExtensionTarget;
+    /// inverts every element of `xs` using Montgomery's trick, i.e. with a single witnessed inverse.
+    /// Like `inverse_quintic_ext`, the circuit is unsatisfiable if any element is zero.
+    fn batch_inverse_quintic_ext(&mut self, xs: &[QuinticExtensionTarget]) -> Vec<QuinticExtensionTarget>;
+    /// returns a[i] / b[i] for every i, or zero where b[i] is zero, using a single witnessed inverse
+    fn batch_div_or_zero_quintic_ext(
+        &mut self,
+        a: &[QuinticExtensionTarget],
+        b: &[QuinticExtensionTarget],
+    ) -> Vec<QuinticExtensionTarget>;
 
     fn any_sqrt_quintic_ext(&mut self, x: QuinticExtensionTarget) -> QuinticExtensionTarget;
     fn try_any_sqrt_quintic_ext(
@@ -120,8 +130,32 @@ pub trait CircuitBuilderGFp5<F: RichField + Extendable<5>> {
 
     fn sgn0_quintic_ext(&mut self, x: QuinticExtensionTarget) -> BoolTarget;
     fn legendre_sym_quintic_ext(&mut self, x: QuinticExtensionTarget) -> Target;
+    /// returns the norm of x, i.e. x^(1 + p + p^2 + p^3 + p^4), which always lies in the base field
+    fn norm_quintic_ext(&mut self, x: QuinticExtensionTarget) -> Target;
     fn frob_quintic_ext(&mut self, x: QuinticExtensionTarget) -> QuinticExtensionTarget;
     fn frob2_quintic_ext(&mut self, x: QuinticExtensionTarget) -> QuinticExtensionTarget;
+    /// returns x^(p^k)
+    fn frob_k_quintic_ext(&mut self, x: QuinticExtensionTarget, k: usize) -> QuinticExtensionTarget;
+
+    /// returns base^exponent, where exponent is range-checked to `num_bits` bits
+    fn exp_quintic_ext(
+        &mut self,
+        base: QuinticExtensionTarget,
+        exponent: Target,
+        num_bits: usize,
+    ) -> QuinticExtensionTarget;
+    /// returns base^exponent, where exponent is given by its little-endian bits
+    fn exp_from_bits_quintic_ext(
+        &mut self,
+        base: QuinticExtensionTarget,
+        exponent_bits: &[BoolTarget],
+    ) -> QuinticExtensionTarget;
+    /// returns base^exponent for a constant exponent, using a sliding-window addition chain
+    fn exp_const_quintic_ext(
+        &mut self,
+        base: QuinticExtensionTarget,
+        exponent: &BigUint,
+    ) -> QuinticExtensionTarget;
 
     fn square_quintic_ext(&mut self, x: QuinticExtensionTarget) -> QuinticExtensionTarget;
     fn add_many_quintic_ext(
@@ -134,10 +168,25 @@ pub trait CircuitBuilderGFp5<F: RichField + Extendable<5>> {
         b: Vec<QuinticExtensionTarget>,
     ) -> QuinticExtensionTarget;
 
+    /// reduces the canonical 320-bit integer encoding of x modulo n, like `Scalar::from_gfp5`.
+    /// The result is slightly biased and there is no domain separation, so Fiat-Shamir challenges
+    /// should be derived with `CircuitBuilderScalar::hash_to_scalar` instead.
     fn encode_quintic_ext_as_scalar(
         &mut self,
         x: QuinticExtensionTarget
     ) -> NonNativeTarget<Scalar>;
+
+    /// returns the canonical little-endian bits of x, 64 per limb, starting from the lowest limb
+    fn split_quintic_ext_to_bits(&mut self, x: QuinticExtensionTarget) -> Vec<BoolTarget>;
+    /// inverse of `split_quintic_ext_to_bits`. The bits are asserted to be canonical.
+    fn quintic_ext_from_bits(&mut self, bits: &[BoolTarget]) -> QuinticExtensionTarget;
+    /// returns the canonical 40-byte little-endian encoding of x, 8 bytes per limb, starting from the lowest limb
+    fn quintic_ext_to_bytes(&mut self, x: QuinticExtensionTarget) -> Vec<Target>;
+    /// inverse of `quintic_ext_to_bytes`. The bytes are range-checked and asserted to be canonical.
+    fn quintic_ext_from_bytes(&mut self, bytes: &[Target]) -> QuinticExtensionTarget;
+    /// asserts that `bits`, the 320-bit little-endian decomposition of a quintic extension element,
+    /// is canonical, i.e. every 64-bit limb is less than p
+    fn assert_canonical_quintic_ext(&mut self, bits: &[BoolTarget]);
 }
 
 pub trait PartialWitnessQuinticExt<F: RichField + Extendable<5>>: Witness<F> {
@@ -562,6 +611,58 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 inverse
             }
 
+            fn batch_inverse_quintic_ext(&mut self, xs: &[QuinticExtensionTarget]) -> Vec<QuinticExtensionTarget> {
+                if xs.is_empty() {
+                    return Vec::new();
+                }
+
+                // prefix_products[i] = xs[0] * ... * xs[i]
+                let mut prefix_products = vec![xs[0]];
+                for &x in xs.iter().skip(1) {
+                    prefix_products.push(self.mul_quintic_ext(*prefix_products.last().unwrap(), x));
+                }
+
+                // propagate the inverse of the product back through the prefix products
+                let mut inv = self.inverse_quintic_ext(prefix_products[xs.len() - 1]);
+                let mut res = vec![inv; xs.len()];
+                for i in (1..xs.len()).rev() {
+                    res[i] = self.mul_quintic_ext(inv, prefix_products[i - 1]);
+                    inv = self.mul_quintic_ext(inv, xs[i]);
+                }
+                res[0] = inv;
+
+                res
+            }
+
+            fn batch_div_or_zero_quintic_ext(
+                &mut self,
+                a: &[QuinticExtensionTarget],
+                b: &[QuinticExtensionTarget],
+            ) -> Vec<QuinticExtensionTarget> {
+                assert_eq!(a.len(), b.len());
+
+                let zero = self.zero_quintic_ext();
+                let one = self.one_quintic_ext();
+
+                // replace zeros by ones so the batch inversion always succeeds
+                let b_is_zero = b.iter().map(|&b| self.is_equal_quintic_ext(b, zero)).collect::<Vec<_>>();
+                let b_or_one = b
+                    .iter()
+                    .zip(b_is_zero.iter())
+                    .map(|(&b, &is_zero)| self.select_quintic_ext(is_zero, one, b))
+                    .collect::<Vec<_>>();
+                let b_inv = self.batch_inverse_quintic_ext(&b_or_one);
+
+                a.iter()
+                    .zip(b_inv)
+                    .zip(b_is_zero)
+                    .map(|((&a, b_inv), is_zero)| {
+                        let quotient = self.mul_quintic_ext(a, b_inv);
+                        self.select_quintic_ext(is_zero, zero, quotient)
+                    })
+                    .collect()
+            }
+
             fn any_sqrt_quintic_ext(
                 &mut self,
                 x: QuinticExtensionTarget,
@@ -596,20 +697,19 @@ macro_rules! impl_circuit_builder_for_extension_degree {
 
                 let mut sign = self.constant_bool(false);
                 let mut is_zero = self.constant_bool(true);
-                for limb in x.to_target_array() {
-                    let bit_decomp = self.split_le_base::<2>(limb, 64);
-
+                let bits = self.split_quintic_ext_to_bits(x);
+                for (limb, bit_decomp) in x.to_target_array().into_iter().zip(bits.chunks(64)) {
                     // sign_i = x_i mod 2
                     // is_zero_i = x_i == 0
                     // SAFETY: targets from bit_decomp guaranteed to contain values of 0 or 1
-                    let sign_i = BoolTarget::new_unsafe(self.sub(one, bit_decomp[0]));
+                    let sign_i = BoolTarget::new_unsafe(self.sub(one, bit_decomp[0].target));
                     let is_zero_i = self.is_equal(limb, zero);
 
                     // sign = sign || (is_zero && sign_i)
                     // is_zero = is_zero && is_zero_i
 
                     // x or y = x + y - xy
-                    let is_zero_and_sign_i = self.and(is_zero_i, sign_i);
+                    let is_zero_and_sign_i = self.and(is_zero, sign_i);
                     let sign_and_is_zero_and_sign_i = self.and(sign, is_zero_and_sign_i);
                     let tmp = self.mul_const_add(
                         -GFp::ONE,
@@ -624,18 +724,8 @@ macro_rules! impl_circuit_builder_for_extension_degree {
             }
 
             fn legendre_sym_quintic_ext(&mut self, x: QuinticExtensionTarget) -> Target {
-                // compute x^r where r = p^4 + p^3 + p^2 + p + 1
-                let frob1 = self.frob_quintic_ext(x);
-                let frob2 = self.frob2_quintic_ext(x);
-                let frob1_times_frob2 = self.mul_quintic_ext(frob1, frob2);
-                let frob2_frob1_times_frob2 = self.frob2_quintic_ext(frob1_times_frob2);
-
-                let x_to_r_minus_1 =
-                    self.mul_quintic_ext(frob1_times_frob2, frob2_frob1_times_frob2);
-                let x_to_r_quintic = self.mul_quintic_ext(x_to_r_minus_1, x);
-
-                // x^r guaranteed to be in base field
-                let QuinticExtensionTarget([y, _, _, _, _]) = x_to_r_quintic;
+                // x is a square in GFp5 iff its norm is a square in GFp
+                let y = self.norm_quintic_ext(x);
 
                 let y31 = self.exp_power_of_2(y, 31);
                 let y63 = self.exp_power_of_2(y31, 32);
@@ -650,6 +740,22 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 self.select(y31_is_zero, zero, res)
             }
 
+            fn norm_quintic_ext(&mut self, x: QuinticExtensionTarget) -> Target {
+                // compute x^r where r = p^4 + p^3 + p^2 + p + 1
+                let frob1 = self.frob_quintic_ext(x);
+                let frob2 = self.frob2_quintic_ext(x);
+                let frob1_times_frob2 = self.mul_quintic_ext(frob1, frob2);
+                let frob2_frob1_times_frob2 = self.frob2_quintic_ext(frob1_times_frob2);
+
+                let x_to_r_minus_1 =
+                    self.mul_quintic_ext(frob1_times_frob2, frob2_frob1_times_frob2);
+                let x_to_r_quintic = self.mul_quintic_ext(x_to_r_minus_1, x);
+
+                // x^r guaranteed to be in base field
+                let QuinticExtensionTarget([y, _, _, _, _]) = x_to_r_quintic;
+                y
+            }
+
             fn frob_quintic_ext(&mut self, x: QuinticExtensionTarget) -> QuinticExtensionTarget {
                 let frob_coeff_1 = GFp::from_canonical_u64(1041288259238279555);
                 let frob_coeff_2 = GFp::from_canonical_u64(15820824984080659046);
@@ -682,6 +788,110 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 QuinticExtensionTarget([c0, c1, c2, c3, c4])
             }
 
+            fn frob_k_quintic_ext(&mut self, x: QuinticExtensionTarget, k: usize) -> QuinticExtensionTarget {
+                if k % 5 == 0 {
+                    return x;
+                }
+
+                // X^(p^k) = zeta^k * X, where zeta = 3^((p - 1) / 5) is a primitive 5th root of unity
+                let zeta = GFp::from_canonical_u64(1041288259238279555);
+
+                let QuinticExtensionTarget(limbs) = x;
+                let mut res = limbs;
+                for i in 1..5 {
+                    let coeff = zeta.exp_u64(((i * k) % 5) as u64);
+                    res[i] = self.mul_const(coeff, limbs[i]);
+                }
+
+                QuinticExtensionTarget(res)
+            }
+
+            fn exp_quintic_ext(
+                &mut self,
+                base: QuinticExtensionTarget,
+                exponent: Target,
+                num_bits: usize,
+            ) -> QuinticExtensionTarget {
+                let exponent_bits = self.split_le(exponent, num_bits);
+                self.exp_from_bits_quintic_ext(base, &exponent_bits)
+            }
+
+            fn exp_from_bits_quintic_ext(
+                &mut self,
+                base: QuinticExtensionTarget,
+                exponent_bits: &[BoolTarget],
+            ) -> QuinticExtensionTarget {
+                let one = self.one_quintic_ext();
+                let mut res = one;
+                for &bit in exponent_bits.iter().rev() {
+                    res = self.square_quintic_ext(res);
+                    let factor = self.select_quintic_ext(bit, base, one);
+                    res = self.mul_quintic_ext(res, factor);
+                }
+
+                res
+            }
+
+            fn exp_const_quintic_ext(
+                &mut self,
+                base: QuinticExtensionTarget,
+                exponent: &BigUint,
+            ) -> QuinticExtensionTarget {
+                const WINDOW_BITS: u64 = 4;
+
+                if exponent.is_zero() {
+                    return self.one_quintic_ext();
+                }
+
+                // split the exponent into odd windows of at most WINDOW_BITS bits, separated by runs of zeros,
+                // as (number of squarings before the window, window value), most significant first
+                let mut windows = Vec::new();
+                let mut squarings = 0;
+                let mut i = exponent.bits() as i64 - 1;
+                while i >= 0 {
+                    if !exponent.bit(i as u64) {
+                        squarings += 1;
+                        i -= 1;
+                        continue;
+                    }
+
+                    let mut j = (i - WINDOW_BITS as i64 + 1).max(0);
+                    while !exponent.bit(j as u64) {
+                        j += 1;
+                    }
+
+                    let width = (i - j + 1) as u64;
+                    let value = (0..width).fold(0, |acc, b| acc | ((exponent.bit(j as u64 + b) as usize) << b));
+                    windows.push((squarings + width as usize, value));
+
+                    squarings = 0;
+                    i = j - 1;
+                }
+
+                // base^1, base^3, base^5, ... up to the largest window
+                let max_window = windows.iter().map(|&(_, value)| value).max().unwrap();
+                let mut odd_powers = vec![base];
+                if max_window > 1 {
+                    let base_squared = self.square_quintic_ext(base);
+                    while odd_powers.len() <= max_window / 2 {
+                        odd_powers.push(self.mul_quintic_ext(*odd_powers.last().unwrap(), base_squared));
+                    }
+                }
+
+                let mut res = odd_powers[windows[0].1 / 2];
+                for &(window_squarings, value) in windows.iter().skip(1) {
+                    for _ in 0..window_squarings {
+                        res = self.square_quintic_ext(res);
+                    }
+                    res = self.mul_quintic_ext(res, odd_powers[value / 2]);
+                }
+                for _ in 0..squarings {
+                    res = self.square_quintic_ext(res);
+                }
+
+                res
+            }
+
             // returns the sqrt(x) such that `sgn0(sqrt(x)) == false`
             fn canonical_sqrt_quintic_ext(
                 &mut self,
@@ -770,22 +980,14 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 &mut self,
                 x: QuinticExtensionTarget,
             ) -> NonNativeTarget<Scalar> {
-                let QuinticExtensionTarget([c0, c1, c2, c3, c4]) = x;
-
-                let bits = [
-                    self.split_le_base::<2>(c0, 64),
-                    self.split_le_base::<2>(c1, 64),
-                    self.split_le_base::<2>(c2, 64),
-                    self.split_le_base::<2>(c3, 64),
-                    self.split_le_base::<2>(c4, 64),
-                ].concat();
+                let bits = self.split_quintic_ext_to_bits(x);
 
                 let limbs_u32 = bits.
                     chunks(32)
                     .map(|chunk| {
                         let mut terms = vec![];
                         for (i, term) in chunk.iter().enumerate() {
-                            terms.push(self.mul_const(GFp::from_canonical_u32(1 << i), *term));
+                            terms.push(self.mul_const(GFp::from_canonical_u32(1 << i), term.target));
                         }
 
                         U32Target(self.add_many(terms))
@@ -794,6 +996,61 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 let biguint = BigUintTarget { limbs: limbs_u32 };
                 self.reduce::<Scalar>(&biguint)
             }
+
+            fn split_quintic_ext_to_bits(&mut self, x: QuinticExtensionTarget) -> Vec<BoolTarget> {
+                let bits = x
+                    .to_target_array()
+                    .into_iter()
+                    .flat_map(|limb| self.split_le_base::<2>(limb, 64))
+                    // SAFETY: targets from split_le_base::<2> guaranteed to contain values of 0 or 1
+                    .map(BoolTarget::new_unsafe)
+                    .collect::<Vec<_>>();
+
+                // a limb less than 2^64 - p has two 64-bit decompositions
+                self.assert_canonical_quintic_ext(&bits);
+                bits
+            }
+
+            fn quintic_ext_from_bits(&mut self, bits: &[BoolTarget]) -> QuinticExtensionTarget {
+                assert_eq!(bits.len(), 320);
+                self.assert_canonical_quintic_ext(bits);
+
+                let two_to_the_32 = GFp::from_canonical_u64(1 << 32);
+                let mut limbs = [self.zero(); 5];
+                for (limb, limb_bits) in limbs.iter_mut().zip(bits.chunks(64)) {
+                    let lo = self.le_sum(limb_bits[..32].iter());
+                    let hi = self.le_sum(limb_bits[32..].iter());
+                    *limb = self.mul_const_add(two_to_the_32, hi, lo);
+                }
+
+                QuinticExtensionTarget(limbs)
+            }
+
+            fn quintic_ext_to_bytes(&mut self, x: QuinticExtensionTarget) -> Vec<Target> {
+                let bits = self.split_quintic_ext_to_bits(x);
+                bits.chunks(8).map(|byte_bits| self.le_sum(byte_bits.iter())).collect()
+            }
+
+            fn quintic_ext_from_bytes(&mut self, bytes: &[Target]) -> QuinticExtensionTarget {
+                assert_eq!(bytes.len(), 40);
+                let bits = bytes.iter().flat_map(|&byte| self.split_le(byte, 8)).collect::<Vec<_>>();
+                self.quintic_ext_from_bits(&bits)
+            }
+
+            fn assert_canonical_quintic_ext(&mut self, bits: &[BoolTarget]) {
+                assert_eq!(bits.len(), 320);
+
+                // p = 2^64 - 2^32 + 1, so a 64-bit limb is less than p iff its high half isn't all ones
+                // or its low half is zero
+                let hi_max = self.constant(GFp::from_canonical_u32(u32::MAX));
+                for limb_bits in bits.chunks(64) {
+                    let lo = self.le_sum(limb_bits[..32].iter());
+                    let hi = self.le_sum(limb_bits[32..].iter());
+                    let hi_is_max = self.is_equal(hi, hi_max);
+                    let should_be_zero = self.mul(hi_is_max.target, lo);
+                    self.assert_zero(should_be_zero);
+                }
+            }
         }
     };
 }
@@ -915,9 +1172,10 @@ mod tests {
     use plonky2::plonk::circuit_data::CircuitConfig;
     use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
     use plonky2_field::types::PrimeField64;
-    use rand::thread_rng;
+    use rand::{thread_rng, Rng};
 
     use super::*;
+    use crate::curve::base_field::quintic_ext_sgn0;
     use crate::curve::scalar_field::biguint_from_array;
     use crate::curve::test_utils::{gfp5_random_non_square, gfp5_random_sgn0_eq_0};
     use crate::gadgets::scalar_field::{CircuitBuilderScalar, PartialWitnessScalar};
@@ -1065,6 +1323,69 @@ mod tests {
         circuit.verify(proof)
     }
 
+    #[test]
+    fn test_batch_inverse_quintic_ext() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let xs_expected = [(); 5].map(|_| GFp5::sample(&mut rng));
+
+        let xs = xs_expected.map(|x| builder.constant_quintic_ext(x));
+        let xs_inv = builder.batch_inverse_quintic_ext(&xs);
+        for &x_inv in xs_inv.iter() {
+            builder.register_quintic_ext_public_input(x_inv);
+        }
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&x_inv, x) in xs_inv.iter().zip(xs_expected) {
+            pw.set_quintic_ext_target(x_inv, x.inverse());
+        }
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_batch_div_or_zero_quintic_ext() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a_expected = [(); 4].map(|_| GFp5::sample(&mut rng));
+        let b_expected = [GFp5::sample(&mut rng), GFp5::ZERO, GFp5::sample(&mut rng), GFp5::ZERO];
+
+        let a = a_expected.map(|a| builder.constant_quintic_ext(a));
+        let b = b_expected.map(|b| builder.constant_quintic_ext(b));
+        let quotients = builder.batch_div_or_zero_quintic_ext(&a, &b);
+        for &quotient in quotients.iter() {
+            builder.register_quintic_ext_public_input(quotient);
+        }
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for ((&quotient, a), b) in quotients.iter().zip(a_expected).zip(b_expected) {
+            let quotient_expected = if b == GFp5::ZERO { GFp5::ZERO } else { a / b };
+            pw.set_quintic_ext_target(quotient, quotient_expected);
+        }
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
     #[test]
     fn test_any_sqrt_quintic_ext() -> Result<()> {
         const D: usize = 2;
@@ -1181,6 +1502,40 @@ mod tests {
         circuit.verify(proof)
     }
 
+    #[test]
+    fn test_sgn0_quintic_ext() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // both signs, and values with leading zero limbs
+        let mut xs_expected = (0..8).map(|_| GFp5::sample(&mut rng)).collect::<Vec<_>>();
+        xs_expected.push(GFp5::ZERO);
+        xs_expected.push(QuinticExtension([GFp::ZERO, GFp::ZERO, GFp::ONE, GFp::ZERO, GFp::TWO]));
+        xs_expected.push(QuinticExtension([GFp::ZERO, GFp::TWO, GFp::ONE, GFp::ZERO, GFp::ZERO]));
+
+        let xs = xs_expected.iter().map(|_| builder.add_virtual_quintic_ext_target()).collect::<Vec<_>>();
+        let signs = xs.iter().map(|&x| builder.sgn0_quintic_ext(x)).collect::<Vec<_>>();
+        for sign in signs.iter() {
+            builder.register_public_input(sign.target);
+        }
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_quintic_ext_targets(&xs, &xs_expected);
+
+        let proof = circuit.prove(pw)?;
+        let signs_expected = xs_expected.iter().map(|x| GFp::from_bool(quintic_ext_sgn0(*x))).collect::<Vec<_>>();
+        assert_eq!(proof.public_inputs, signs_expected);
+        circuit.verify(proof)
+    }
+
     #[test]
     fn test_legendre_sym_quintic_ext() -> Result<()> {
         const D: usize = 2;
@@ -1239,6 +1594,150 @@ mod tests {
         circuit.verify(proof)
     }
 
+    #[test]
+    fn test_norm_and_frob_k_quintic_ext() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x_expected = GFp5::sample(&mut rng);
+        let p = GFp::order();
+        let norm_expected = x_expected.exp_biguint(&((p.pow(5) - 1u32) / (&p - 1u32)));
+
+        let x = builder.constant_quintic_ext(x_expected);
+        let norm = builder.norm_quintic_ext(x);
+        builder.register_public_input(norm);
+
+        let mut frobs = Vec::new();
+        for k in 0..7 {
+            let frob = builder.frob_k_quintic_ext(x, k);
+            builder.register_quintic_ext_public_input(frob);
+            frobs.push((frob, x_expected.exp_biguint(&p.pow(k as u32))));
+        }
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(norm, norm_expected.0[0]);
+        for (frob, frob_expected) in frobs {
+            pw.set_quintic_ext_target(frob, frob_expected);
+        }
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_exp_quintic_ext() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x_expected = GFp5::sample(&mut rng);
+        let e = rng.gen::<u32>() as u64;
+        let y_expected = x_expected.exp_u64(e);
+
+        let x = builder.constant_quintic_ext(x_expected);
+        let e = builder.constant(GFp::from_canonical_u64(e));
+        let y = builder.exp_quintic_ext(x, e, 32);
+        builder.register_quintic_ext_public_input(y);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_quintic_ext_target(y, y_expected);
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_exp_const_quintic_ext() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x_expected = GFp5::sample(&mut rng);
+        let x = builder.constant_quintic_ext(x_expected);
+
+        let random_exponent = BigUint::from_slice(&[(); 10].map(|_| rng.gen::<u32>()));
+        let exponents = [0u32, 1, 2, 3, 16, 17, 0b1000_0001_0110].map(BigUint::from);
+
+        let mut ys = Vec::new();
+        for e in exponents.iter().chain([random_exponent].iter()) {
+            let y = builder.exp_const_quintic_ext(x, e);
+            builder.register_quintic_ext_public_input(y);
+            ys.push((y, x_expected.exp_biguint(e)));
+        }
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (y, y_expected) in ys {
+            pw.set_quintic_ext_target(y, y_expected);
+        }
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_quintic_ext_bits_and_bytes() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x_expected = GFp5::sample(&mut rng);
+        let bytes_expected = x_expected
+            .0
+            .iter()
+            .flat_map(|limb| limb.to_canonical_u64().to_le_bytes())
+            .collect::<Vec<_>>();
+
+        let x = builder.constant_quintic_ext(x_expected);
+        let bytes = builder.quintic_ext_to_bytes(x);
+        builder.register_public_inputs(&bytes);
+
+        let bits = builder.split_quintic_ext_to_bits(x);
+        let x_from_bits = builder.quintic_ext_from_bits(&bits);
+        builder.connect_quintic_ext(x, x_from_bits);
+
+        let bytes_in = builder.add_virtual_targets(40);
+        let x_from_bytes = builder.quintic_ext_from_bytes(&bytes_in);
+        builder.connect_quintic_ext(x, x_from_bytes);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for ((&byte, &byte_in), &value) in bytes.iter().zip(bytes_in.iter()).zip(bytes_expected.iter()) {
+            pw.set_target(byte, GFp::from_canonical_u8(value));
+            pw.set_target(byte_in, GFp::from_canonical_u8(value));
+        }
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
     #[test]
     fn test_encode_as_scalar() -> Result<()> {
         const D: usize = 2;
@@ -0,0 +1,200 @@
+use alloc::vec::Vec;
+use plonky2::iop::witness::Witness;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2_ecdsa::gadgets::biguint::BigUintTarget;
+use plonky2_ecdsa::gadgets::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+
+use crate::curve::credential::{credential_generator, IssuerParams, PresentationCommitments, PresentationOpening};
+use crate::curve::curve::Point;
+use crate::curve::scalar_field::Scalar;
+use crate::curve::GFp;
+use crate::gadgets::curve::{CircuitBuilderEcGFp5, CurveTarget, PartialWitnessCurve};
+use crate::gadgets::scalar_field::{CircuitBuilderScalar, PartialWitnessScalar};
+
+/// A credential presentation, proved in circuit instead of with a Sigma proof.
+/// Created with `add_virtual_credential_presentation_target`.
+///
+/// `u`, `attribute_commitments` and `v` are what the issuer checks, and should be registered as public inputs.
+/// The attributes stay private, and can be constrained further.
+#[derive(Clone, Debug)]
+pub struct CredentialPresentationTarget {
+    pub u: CurveTarget,
+    pub attribute_commitments: Vec<CurveTarget>,
+    pub v: CurveTarget,
+    pub attributes: Vec<NonNativeTarget<Scalar>>,
+    pub blindings: Vec<NonNativeTarget<Scalar>>,
+    pub r: NonNativeTarget<Scalar>,
+    // the limbs of the attributes, then of the blindings and r, for the witness
+    limbs: Vec<BigUintTarget>,
+}
+
+pub trait CircuitBuilderCredential {
+    fn add_virtual_credential_presentation_target(&mut self, num_attributes: usize) -> CredentialPresentationTarget;
+
+    /// Proves knowledge of a credential of the issuer with public keys `issuer_x` (the `X_i` of `IssuerParams`)
+    /// on the attributes of `presentation`: that every attribute commitment is m_i * U + z_i * H,
+    /// and that V = sum z_i * X_i - r * G. The issuer then checks that V is `CredentialSecretKey::presentation_v`.
+    fn verify_credential_presentation(&mut self, issuer_x: &[CurveTarget], presentation: &CredentialPresentationTarget);
+}
+
+macro_rules! impl_circuit_builder_for_extension_degree {
+    ($degree:literal) => {
+        impl CircuitBuilderCredential for CircuitBuilder<GFp, $degree> {
+            fn add_virtual_credential_presentation_target(&mut self, num_attributes: usize) -> CredentialPresentationTarget {
+                let u = self.add_virtual_curve_target();
+                let attribute_commitments = (0..num_attributes).map(|_| self.add_virtual_curve_target()).collect();
+                let v = self.add_virtual_curve_target();
+
+                let attributes = (0..num_attributes).map(|_| self.add_virtual_scalar_target()).collect::<Vec<_>>();
+                let blindings = (0..num_attributes).map(|_| self.add_virtual_scalar_target()).collect::<Vec<_>>();
+                let r = self.add_virtual_scalar_target();
+                let limbs = attributes
+                    .iter()
+                    .chain(blindings.iter())
+                    .chain([&r])
+                    .map(|x| self.nonnative_to_canonical_biguint(x))
+                    .collect();
+
+                CredentialPresentationTarget { u, attribute_commitments, v, attributes, blindings, r, limbs }
+            }
+
+            fn verify_credential_presentation(&mut self, issuer_x: &[CurveTarget], presentation: &CredentialPresentationTarget) {
+                let n = issuer_x.len();
+                assert_eq!(presentation.attribute_commitments.len(), n);
+                assert_eq!(presentation.attributes.len(), n);
+                assert_eq!(presentation.blindings.len(), n);
+
+                // U = 0 would make any attributes valid
+                let zero = self.curve_zero();
+                let u_is_zero = self.curve_eq(presentation.u, zero);
+                self.assert_zero(u_is_zero.target);
+
+                // C_i = m_i * U + z_i * H
+                let h = self.curve_constant(credential_generator().to_weierstrass());
+                for ((&c_i, m_i), z_i) in presentation
+                    .attribute_commitments
+                    .iter()
+                    .zip(presentation.attributes.iter())
+                    .zip(presentation.blindings.iter())
+                {
+                    let c_i_expected = self.curve_muladd_2(presentation.u, h, m_i, z_i);
+                    let is_equal = self.curve_eq(c_i, c_i_expected);
+                    self.assert_one(is_equal.target);
+                }
+
+                // V = sum z_i * X_i - r * G
+                let r_g = self.curve_scalar_mul_const(Point::GENERATOR, &presentation.r);
+                let mut v_expected = self.curve_neg(r_g);
+                for (x, z) in issuer_x.chunks(2).zip(presentation.blindings.chunks(2)) {
+                    let term = match (x, z) {
+                        ([x_a, x_b], [z_a, z_b]) => self.curve_muladd_2(*x_a, *x_b, z_a, z_b),
+                        ([x_a], [z_a]) => self.curve_scalar_mul(*x_a, z_a),
+                        _ => unreachable!(),
+                    };
+                    v_expected = self.curve_add(v_expected, term);
+                }
+                let is_equal = self.curve_eq(presentation.v, v_expected);
+                self.assert_one(is_equal.target);
+            }
+        }
+    };
+}
+
+impl_circuit_builder_for_extension_degree!(1);
+impl_circuit_builder_for_extension_degree!(2);
+impl_circuit_builder_for_extension_degree!(4);
+impl_circuit_builder_for_extension_degree!(5);
+
+pub trait PartialWitnessCredential: Witness<GFp> {
+    fn set_credential_presentation_target(
+        &mut self,
+        target: &CredentialPresentationTarget,
+        params: &IssuerParams,
+        commitments: &PresentationCommitments,
+        opening: &PresentationOpening,
+    );
+}
+
+impl<W: Witness<GFp>> PartialWitnessCredential for W {
+    fn set_credential_presentation_target(
+        &mut self,
+        target: &CredentialPresentationTarget,
+        params: &IssuerParams,
+        commitments: &PresentationCommitments,
+        opening: &PresentationOpening,
+    ) {
+        assert_eq!(target.attribute_commitments.len(), commitments.attribute_commitments.len());
+        assert_eq!(target.attributes.len(), opening.attributes.len());
+
+        self.set_curve_target(target.u, commitments.u.to_weierstrass());
+        for (&t, c_i) in target.attribute_commitments.iter().zip(commitments.attribute_commitments.iter()) {
+            self.set_curve_target(t, c_i.to_weierstrass());
+        }
+        self.set_curve_target(target.v, opening.v(params).to_weierstrass());
+
+        let values = opening
+            .attributes
+            .iter()
+            .chain(opening.blindings.iter())
+            .chain([&opening.r])
+            .copied();
+        for (limbs, x) in target.limbs.iter().zip(values) {
+            self.set_scalar_target(limbs, x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::curve::credential::CredentialSecretKey;
+
+    #[test]
+    fn test_verify_credential_presentation() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let sk = CredentialSecretKey::sample(3, &mut rng);
+        let params = sk.params();
+        let attributes = vec![Scalar::from_canonical_u64(1990), Scalar::sample(&mut rng), Scalar::sample(&mut rng)];
+        let (credential, _) = sk.issue(&attributes, &mut rng);
+        let (commitments, opening) = credential.commit(&attributes, &mut rng);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let issuer_x = params.x.iter().map(|x_i| builder.curve_constant(x_i.to_weierstrass())).collect::<Vec<_>>();
+        let presentation = builder.add_virtual_credential_presentation_target(attributes.len());
+        builder.register_curve_public_input(presentation.u);
+        for &c_i in presentation.attribute_commitments.iter() {
+            builder.register_curve_public_input(c_i);
+        }
+        builder.register_curve_public_input(presentation.v);
+        builder.verify_credential_presentation(&issuer_x, &presentation);
+
+        // e.g. reveal the first attribute, keeping the others hidden
+        let birth_year = builder.constant_nonnative(Scalar::from_canonical_u64(1990));
+        builder.connect_nonnative(&presentation.attributes[0], &birth_year);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_credential_presentation_target(&presentation, &params, &commitments, &opening);
+
+        // the issuer side check of V
+        assert!(sk.presentation_v(&commitments).unwrap().equals(opening.v(&params)));
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+}
@@ -1,14 +1,22 @@
-use crate::curve::scalar_field::Scalar;
+use crate::curve::hash_to_curve::{HASH_TO_CURVE_NUM_OUTPUTS, SSWU_Z};
+use crate::curve::nullifier::{NULLIFIER_CHALLENGE_DOMAIN, NULLIFIER_HASH_DOMAIN};
+use crate::curve::scalar_field::{hash_to_scalar_prefix, Scalar, Signed161};
+use crate::curve::schnorr::SCHNORR_CHALLENGE_DOMAIN;
 use crate::curve::{curve::{Point, WeierstrassPoint}, GFp, GFp5};
 use crate::gadgets::base_field::{CircuitBuilderGFp5, QuinticExtensionTarget};
-use plonky2::field::types::Field; 
+use crate::gadgets::scalar_field::{signed_digits_from_bits, CircuitBuilderScalar, SignedDigitTarget};
+use plonky2::field::types::Field;
+use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::hash::hash_types::RichField;
+use plonky2::hash::poseidon::PoseidonHash;
 use plonky2::iop::target::BoolTarget;
 use plonky2::iop::target::Target;
-use plonky2::iop::witness::Witness;
+use plonky2::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
-use plonky2_ecdsa::gadgets::nonnative::NonNativeTarget;
-use plonky2_ecdsa::gadgets::split_nonnative::CircuitBuilderSplit;
+use num::{BigUint, One};
+use plonky2_ecdsa::gadgets::biguint::{BigUintTarget, CircuitBuilderBiguint, WitnessBigUint};
+use plonky2_ecdsa::gadgets::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+use plonky2_u32::gadgets::arithmetic_u32::U32Target;
 use plonky2_field::extension::Extendable;
 use plonky2_field::extension::quintic::QuinticExtension;
 use plonky2_field::goldilocks_field::GoldilocksField;
@@ -29,21 +37,97 @@ pub trait CircuitBuilderEcGFp5 {
     fn curve_eq(&mut self, a: CurveTarget, b: CurveTarget) -> BoolTarget;
     fn curve_select(&mut self, cond: BoolTarget, a: CurveTarget, b: CurveTarget) -> CurveTarget;
     fn curve_random_access(&mut self, access_index: Target, v: &[CurveTarget]) -> CurveTarget;
+    fn curve_signed_random_access(&mut self, digit: SignedDigitTarget, odd_window: &[CurveTarget]) -> CurveTarget;
 
+    fn curve_neg(&mut self, a: CurveTarget) -> CurveTarget;
+    fn curve_conditional_neg(&mut self, a: CurveTarget, cond: BoolTarget) -> CurveTarget;
     fn curve_add(&mut self, a: CurveTarget, b: CurveTarget) -> CurveTarget;
     fn curve_add_spec(&mut self, a: CurveTarget, b: CurveTarget) -> CurveTarget;
+    fn curve_sub(&mut self, a: CurveTarget, b: CurveTarget) -> CurveTarget;
     fn curve_double(&mut self, a: CurveTarget) -> CurveTarget;
 
+    /// Adds `a` and `b` using the incomplete affine formula, which is cheaper than `curve_add`.
+    /// Requires that neither input is the point at infinity and that a.x != b.x (i.e. a != b and a != -b).
+    /// Both preconditions are asserted, so the circuit is unsatisfiable if they don't hold.
+    fn curve_add_unchecked(&mut self, a: CurveTarget, b: CurveTarget) -> CurveTarget;
+    /// Doubles `a` using the incomplete affine formula, which is cheaper than `curve_double`.
+    /// Requires that `a` is not the point at infinity, which is asserted. The Weierstrass curve has order 2n,
+    /// so it does have a point with y = 0, N = (2/3, 0). Such an input is rejected too: the slope is then
+    /// (3 * x^2 + A) / 0 with a nonzero numerator, since the curve is nonsingular, so the constraint of
+    /// `div_quintic_ext` can't be satisfied. Points of the subgroup of order n never have y = 0.
+    fn curve_double_unchecked(&mut self, a: CurveTarget) -> CurveTarget;
+
     fn precompute_window(&mut self, a: CurveTarget, window_bits: usize) -> Vec<CurveTarget>;
+    fn precompute_odd_window(&mut self, a: CurveTarget, window_bits: usize) -> Vec<CurveTarget>;
     fn curve_scalar_mul(&mut self, a: CurveTarget, scalar: &NonNativeTarget<Scalar>) -> CurveTarget;
+    fn curve_scalar_mul_windowed(&mut self, a: CurveTarget, scalar: &NonNativeTarget<Scalar>, window_bits: usize) -> CurveTarget;
+    /// Same as `curve_scalar_mul`, but uses `curve_add_unchecked` and `curve_double_unchecked` everywhere except for the last addition.
+    /// Unlike `curve_scalar_mul`, `a` must be in the subgroup of order n (or be the point at infinity): for other points,
+    /// such as N = (2/3, 0) or the points of order 2n, `curve_double_unchecked` can't be satisfied.
+    fn curve_scalar_mul_incomplete(&mut self, a: CurveTarget, scalar: &NonNativeTarget<Scalar>) -> CurveTarget;
+    /// Same as `curve_scalar_mul_incomplete`, with windows of `window_bits` bits. `window_bits` can't be 11, where
+    /// the top digits of the scalar may add up to more than n.
+    fn curve_scalar_mul_incomplete_windowed(&mut self, a: CurveTarget, scalar: &NonNativeTarget<Scalar>, window_bits: usize) -> CurveTarget;
+
+    /// Multiplies `a` by the integer whose little-endian binary representation is `bits`.
+    /// Useful for short scalars such as Fiat-Shamir challenges, since no non-native arithmetic is involved.
+    fn curve_scalar_mul_bits(&mut self, a: CurveTarget, bits: &[BoolTarget]) -> CurveTarget;
+    /// Multiplies `a` by the u64 given by two little-endian u32 limbs.
+    fn curve_scalar_mul_u64(&mut self, a: CurveTarget, limbs: [U32Target; 2]) -> CurveTarget;
+    /// Multiplies `a` by the u128 given by four little-endian u32 limbs.
+    fn curve_scalar_mul_u128(&mut self, a: CurveTarget, limbs: [U32Target; 4]) -> CurveTarget;
 
     fn precompute_window_const(&mut self, point: Point, window_bits: usize) -> Vec<CurveTarget>;
+    fn precompute_odd_window_const(&mut self, point: Point, window_bits: usize) -> Vec<CurveTarget>;
     fn curve_scalar_mul_const(&mut self, point: Point, scalar: &NonNativeTarget<Scalar>) -> CurveTarget;
+    fn curve_scalar_mul_const_windowed(&mut self, point: Point, scalar: &NonNativeTarget<Scalar>, window_bits: usize) -> CurveTarget;
 
     fn curve_encode_to_quintic_ext(&mut self, a: CurveTarget) -> QuinticExtensionTarget;
     fn curve_decode_from_quintic_ext(&mut self, w: QuinticExtensionTarget) -> CurveTarget;
 
     fn curve_muladd_2(&mut self, a: CurveTarget, b: CurveTarget, scalar_a: &NonNativeTarget<Scalar>, scalar_b: &NonNativeTarget<Scalar>) -> CurveTarget;
+    fn curve_muladd_2_windowed(&mut self, a: CurveTarget, b: CurveTarget, scalar_a: &NonNativeTarget<Scalar>, scalar_b: &NonNativeTarget<Scalar>, window_bits: usize) -> CurveTarget;
+
+    /// Asserts that `s * G + k * q == r`, where G is the conventional generator. This is the main check in Schnorr signature verification.
+    /// Like `Point::verify_muladd_vartime`, k is split into c0 / c1 (mod n) using `Scalar::lagrange`, so every multiplier fits in 161 bits
+    /// and the doubling chain is half as long as the one in `curve_muladd_2`.
+    fn curve_verify_muladd(&mut self, q: CurveTarget, s: &NonNativeTarget<Scalar>, k: &NonNativeTarget<Scalar>, r: CurveTarget);
+
+    /// Maps `u` to a curve point with the simplified SWU map. Doubling the result gives the same point as
+    /// doubling `hash_to_curve::map_to_curve(u)`.
+    /// WARNING: the result may lie outside the subgroup of order n, where the other gadgets don't
+    /// give the same results as their native counterparts. Double it (i.e. clear the cofactor) first.
+    fn curve_map_to_curve(&mut self, u: QuinticExtensionTarget) -> CurveTarget;
+    /// Computes exactly the same function as `hash_to_curve::hash_to_curve`.
+    fn curve_hash_to_curve(&mut self, domain: &[u8], inputs: &[Target]) -> CurveTarget;
+
+    /// Checks the PLUME-style `nullifier` of the secret key of `pk` for `message`, along with its proof (`c`, `s`),
+    /// like `nullifier::verify_nullifier`.
+    ///
+    /// This only ties the nullifier to `pk`, which is a free witness unless the caller constrains it. To get one
+    /// nullifier per member of a group, the caller must also bind `pk` to the group, e.g. by making it a public input
+    /// or by proving its membership in a public set of keys (a Merkle root); otherwise any fresh key will do.
+    fn curve_verify_nullifier(
+        &mut self,
+        pk: CurveTarget,
+        message: &[Target],
+        nullifier: CurveTarget,
+        c: &NonNativeTarget<Scalar>,
+        s: &NonNativeTarget<Scalar>,
+    );
+
+    /// Checks the Schnorr signature (`s`, `e`) of `message` under `pk`, like `schnorr::schnorr_verify`.
+    fn curve_verify_schnorr(&mut self, pk: CurveTarget, message: &[Target], s: &NonNativeTarget<Scalar>, e: &NonNativeTarget<Scalar>);
+    /// Checks the adaptor pre-signature (`s`, `e`) of `message` under `pk` for the adaptor point `t`,
+    /// like `adaptor::adaptor_pre_verify`.
+    fn curve_verify_adaptor_pre_signature(
+        &mut self,
+        pk: CurveTarget,
+        t: CurveTarget,
+        message: &[Target],
+        s: &NonNativeTarget<Scalar>,
+        e: &NonNativeTarget<Scalar>,
+    );
 }
 
 macro_rules! impl_circuit_builder_for_extension_degree {
@@ -114,6 +198,11 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 ))
             }
 
+            // One random access per limb, through `random_access_quintic_ext`. plonky2 0.1.3 has no lookup gates, so there
+            // is no lookup mode. A packed gate fetching the 10 limbs of an entry at once would need a routed wire per limb of
+            // every entry: 91 for an 8-entry odd window, more than the 80 of the standard recursion config, so 2 rows per
+            // lookup. 8 `RandomAccessGate` copies of 8 entries fit in a row, so a lookup in an odd window takes 1.25 rows.
+            // Lookups are also only about 3% of the 3451 gates of `curve_scalar_mul`.
             fn curve_random_access(
                 &mut self,
                 access_index: Target,
@@ -137,6 +226,41 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 ))
             }
 
+            // `odd_window` must contain [P, 3P, 5P, ...], as built by `precompute_odd_window`
+            fn curve_signed_random_access(
+                &mut self,
+                digit: SignedDigitTarget,
+                odd_window: &[CurveTarget],
+            ) -> CurveTarget {
+                // kP is the point at infinity iff P is, since k is odd and less than n. So every entry has the same
+                // is_inf flag and we only need to look up the coordinates, which saves a random access per lookup
+                let mut xs = Vec::new();
+                let mut ys = Vec::new();
+                for &CurveTarget(([x, y], _)) in odd_window {
+                    xs.push(x);
+                    ys.push(y);
+                }
+                let CurveTarget((_, is_inf)) = odd_window[0];
+
+                let x = self.random_access_quintic_ext(digit.index, &xs);
+                let y = self.random_access_quintic_ext(digit.index, &ys);
+                let neg_y = self.neg_quintic_ext(y);
+                let y = self.select_quintic_ext(digit.is_neg, neg_y, y);
+
+                CurveTarget(([x, y], is_inf))
+            }
+
+            fn curve_neg(&mut self, a: CurveTarget) -> CurveTarget {
+                let CurveTarget(([x, y], is_inf)) = a;
+                CurveTarget(([x, self.neg_quintic_ext(y)], is_inf))
+            }
+
+            fn curve_conditional_neg(&mut self, a: CurveTarget, cond: BoolTarget) -> CurveTarget {
+                let CurveTarget(([x, y], is_inf)) = a;
+                let neg_y = self.neg_quintic_ext(y);
+                CurveTarget(([x, self.select_quintic_ext(cond, neg_y, y)], is_inf))
+            }
+
             fn curve_add(&mut self, a: CurveTarget, b: CurveTarget) -> CurveTarget {
                 let CurveTarget(([x1, y1], a_is_inf)) = a;
                 let CurveTarget(([x2, y2], b_is_inf)) = b;
@@ -194,6 +318,11 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 CurveTarget(([x3, y3], BoolTarget::new_unsafe(self.zero())))
             }
 
+            fn curve_sub(&mut self, a: CurveTarget, b: CurveTarget) -> CurveTarget {
+                let neg_b = self.curve_neg(b);
+                self.curve_add(a, neg_b)
+            }
+
             fn curve_double(&mut self, a: CurveTarget) -> CurveTarget {
                 let CurveTarget(([x, y], is_inf)) = a;
 
@@ -215,6 +344,51 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 CurveTarget(([x2, y2], is_inf))
             }
 
+            fn curve_add_unchecked(&mut self, a: CurveTarget, b: CurveTarget) -> CurveTarget {
+                let CurveTarget(([x1, y1], a_is_inf)) = a;
+                let CurveTarget(([x2, y2], b_is_inf)) = b;
+                self.assert_zero(a_is_inf.target);
+                self.assert_zero(b_is_inf.target);
+
+                // inverting x2 - x1 also asserts that x1 != x2
+                let lambda_0 = self.sub_quintic_ext(y2, y1);
+                let lambda_1 = self.sub_quintic_ext(x2, x1);
+                let lambda_1_inv = self.inverse_quintic_ext(lambda_1);
+                let lambda = self.mul_quintic_ext(lambda_0, lambda_1_inv);
+
+                let mut x3 = self.square_quintic_ext(lambda);
+                x3 = self.sub_quintic_ext(x3, x1);
+                x3 = self.sub_quintic_ext(x3, x2);
+
+                let mut y3 = self.sub_quintic_ext(x1, x3);
+                y3 = self.mul_quintic_ext(lambda, y3);
+                y3 = self.sub_quintic_ext(y3, y1);
+
+                CurveTarget(([x3, y3], self._false()))
+            }
+
+            fn curve_double_unchecked(&mut self, a: CurveTarget) -> CurveTarget {
+                let CurveTarget(([x, y], is_inf)) = a;
+                self.assert_zero(is_inf.target);
+
+                let mut lambda_0 = self.square_quintic_ext(x);
+                lambda_0 = self.triple_quintic_ext(lambda_0);
+                lambda_0 = self.add_const_quintic_ext(lambda_0, WeierstrassPoint::A);
+                let lambda_1 = self.double_quintic_ext(y);
+
+                let lambda = self.div_quintic_ext(lambda_0, lambda_1);
+
+                let mut x2 = self.square_quintic_ext(lambda);
+                let two_x = self.double_quintic_ext(x);
+                x2 = self.sub_quintic_ext(x2, two_x);
+
+                let mut y2 = self.sub_quintic_ext(x, x2);
+                y2 = self.mul_quintic_ext(lambda, y2);
+                y2 = self.sub_quintic_ext(y2, y);
+
+                CurveTarget(([x2, y2], self._false()))
+            }
+
             fn precompute_window(&mut self, a: CurveTarget, window_bits: usize) -> Vec<CurveTarget> {
                 debug_assert!(window_bits > 1);
                 let mut multiples = vec![self.curve_zero()];
@@ -230,18 +404,122 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 multiples
             }
 
-            // TODO optimize
+            // returns [P, 3P, 5P, ..., (2^window_bits - 1)P]
+            fn precompute_odd_window(&mut self, a: CurveTarget, window_bits: usize) -> Vec<CurveTarget> {
+                debug_assert!(window_bits > 1);
+                let a_doubled = self.curve_double(a);
+                let mut multiples = vec![a];
+
+                for _ in 1..(1 << (window_bits - 1)) {
+                    multiples.push(
+                        self.curve_add(multiples.last().unwrap().clone(), a_doubled)
+                    );
+                }
+
+                multiples
+            }
+
             fn curve_scalar_mul(
                 &mut self,
                 a: CurveTarget,
                 scalar: &NonNativeTarget<Scalar>,
             ) -> CurveTarget {
-                let window = self.precompute_window(a, 4);
-                let four_bit_limbs = self.split_nonnative_to_4_bit_limbs(&scalar);
+                self.curve_scalar_mul_windowed(a, scalar, 4)
+            }
+
+            fn curve_scalar_mul_windowed(
+                &mut self,
+                a: CurveTarget,
+                scalar: &NonNativeTarget<Scalar>,
+                window_bits: usize,
+            ) -> CurveTarget {
+                let window = self.precompute_odd_window(a, window_bits);
+                let digits = self.recode_scalar_signed(scalar, window_bits);
+
+                let num_digits = digits.len();
+                let mut res = self.curve_signed_random_access(digits[num_digits - 1], &window);
+                for &digit in digits.iter().rev().skip(1) {
+                    for _ in 0..window_bits {
+                        res = self.curve_double(res);
+                    }
+
+                    let addend = self.curve_signed_random_access(digit, &window);
+                    res = self.curve_add(res, addend);
+                }
+
+                res
+            }
+
+            fn curve_scalar_mul_incomplete(
+                &mut self,
+                a: CurveTarget,
+                scalar: &NonNativeTarget<Scalar>,
+            ) -> CurveTarget {
+                self.curve_scalar_mul_incomplete_windowed(a, scalar, 4)
+            }
+
+            fn curve_scalar_mul_incomplete_windowed(
+                &mut self,
+                a: CurveTarget,
+                scalar: &NonNativeTarget<Scalar>,
+                window_bits: usize,
+            ) -> CurveTarget {
+                debug_assert!(window_bits > 1);
+                let digits = self.recode_scalar_signed(scalar, window_bits);
+                let num_digits = digits.len();
+                // 2^318 < n, see below
+                assert!(window_bits * (num_digits - 1) <= 318, "the top digits may add up to more than n");
+
+                // the incomplete formulas can't take the point at infinity, so we multiply the generator instead
+                // and replace the result at the end
+                let CurveTarget((_, a_is_inf)) = a;
+                let g = self.curve_constant(Point::GENERATOR.to_weierstrass());
+                let a = self.curve_select(a_is_inf, g, a);
+
+                // kP + 2P with k in {1, 3, ..., 2^w - 3} never hits an exceptional case, since k != +-2 mod n
+                let a_doubled = self.curve_double_unchecked(a);
+                let mut window = vec![a];
+                for _ in 1..(1 << (window_bits - 1)) {
+                    window.push(self.curve_add_unchecked(*window.last().unwrap(), a_doubled));
+                }
+
+                // The digits are odd, so after processing the top j digits the accumulator is m * P for some odd m with
+                // |m| <= 2^(wj) - 1. Doubling never hits the point at infinity, since m != 0 mod n. Until the last digit,
+                // j <= k - 1, so |2^w * m| + 2^w - 1 <= 2^(w(k-1)) - 1 < n, and 2^w * m is even, so it's never +-e for
+                // an odd digit e: the accumulator never shares an x-coordinate with the addend.
+                // The last addition may wrap around n, so it uses the complete formula.
+                let mut res = self.curve_signed_random_access(digits[num_digits - 1], &window);
+                for (i, &digit) in digits.iter().enumerate().rev().skip(1) {
+                    for _ in 0..window_bits {
+                        res = self.curve_double_unchecked(res);
+                    }
+
+                    let addend = self.curve_signed_random_access(digit, &window);
+                    res = if i == 0 {
+                        self.curve_add(res, addend)
+                    } else {
+                        self.curve_add_unchecked(res, addend)
+                    };
+                }
+
+                let zero = self.curve_zero();
+                self.curve_select(a_is_inf, zero, res)
+            }
 
-                let num_limbs = four_bit_limbs.len();
-                let mut res = self.curve_random_access(four_bit_limbs[num_limbs - 1], &window);
-                for limb in four_bit_limbs.into_iter().rev().skip(1) {
+            fn curve_scalar_mul_bits(&mut self, a: CurveTarget, bits: &[BoolTarget]) -> CurveTarget {
+                if bits.is_empty() {
+                    return self.curve_zero();
+                }
+
+                let window = self.precompute_window(a, 4);
+                let limbs = bits
+                    .chunks(4)
+                    .map(|limb_bits| self.le_sum(limb_bits.iter()))
+                    .collect::<Vec<_>>();
+
+                let num_limbs = limbs.len();
+                let mut res = self.curve_random_access(limbs[num_limbs - 1], &window);
+                for &limb in limbs.iter().rev().skip(1) {
                     for _ in 0..4 {
                         res = self.curve_double(res);
                     }
@@ -253,6 +531,15 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 res
             }
 
+            fn curve_scalar_mul_u64(&mut self, a: CurveTarget, limbs: [U32Target; 2]) -> CurveTarget {
+                let bits = limbs.iter().flat_map(|limb| self.split_le(limb.0, 32)).collect::<Vec<_>>();
+                self.curve_scalar_mul_bits(a, &bits)
+            }
+
+            fn curve_scalar_mul_u128(&mut self, a: CurveTarget, limbs: [U32Target; 4]) -> CurveTarget {
+                let bits = limbs.iter().flat_map(|limb| self.split_le(limb.0, 32)).collect::<Vec<_>>();
+                self.curve_scalar_mul_bits(a, &bits)
+            }
 
             fn precompute_window_const(&mut self, point: Point, window_bits: usize) -> Vec<CurveTarget> {
                 let mut curr = point;
@@ -266,18 +553,36 @@ macro_rules! impl_circuit_builder_for_extension_degree {
                 multiples
             }
 
+            // returns [P, 3P, 5P, ..., (2^window_bits - 1)P]
+            fn precompute_odd_window_const(&mut self, point: Point, window_bits: usize) -> Vec<CurveTarget> {
+                let point_doubled = point.double();
+                let mut curr = point;
+                let mut multiples = Vec::new();
+
+                for _ in 0..(1 << (window_bits - 1)) {
+                    multiples.push(self.curve_constant(curr.to_weierstrass()));
+                    curr += point_doubled;
+                }
+
+                multiples
+            }
+
             fn curve_scalar_mul_const(&mut self, point: Point, scalar: &NonNativeTarget<Scalar>, ) -> CurveTarget {
-                let window = self.precompute_window_const(point, 4);
-                let four_bit_limbs = self.split_nonnative_to_4_bit_limbs(&scalar);
+                self.curve_scalar_mul_const_windowed(point, scalar, 4)
+            }
 
-                let num_limbs = four_bit_limbs.len();
-                let mut res = self.curve_random_access(four_bit_limbs[num_limbs - 1], &window);
-                for limb in four_bit_limbs.into_iter().rev().skip(1) {
-                    for _ in 0..4 {
+            fn curve_scalar_mul_const_windowed(&mut self, point: Point, scalar: &NonNativeTarget<Scalar>, window_bits: usize) -> CurveTarget {
+                let window = self.precompute_odd_window_const(point, window_bits);
+                let digits = self.recode_scalar_signed(scalar, window_bits);
+
+                let num_digits = digits.len();
+                let mut res = self.curve_signed_random_access(digits[num_digits - 1], &window);
+                for &digit in digits.iter().rev().skip(1) {
+                    for _ in 0..window_bits {
                         res = self.curve_double(res);
                     }
 
-                    let addend = self.curve_random_access(limb, &window);
+                    let addend = self.curve_signed_random_access(digit, &window);
                     res = self.curve_add(res, addend);
                 }
 
@@ -336,32 +641,166 @@ macro_rules! impl_circuit_builder_for_extension_degree {
             }
 
             fn curve_muladd_2(&mut self, a: CurveTarget, b: CurveTarget, scalar_a: &NonNativeTarget<Scalar>, scalar_b: &NonNativeTarget<Scalar>) -> CurveTarget {
-                let a_window = self.precompute_window(a, 4);
-                let a_four_bit_limbs = self.split_nonnative_to_4_bit_limbs(&scalar_a);
+                self.curve_muladd_2_windowed(a, b, scalar_a, scalar_b, 4)
+            }
+
+            fn curve_muladd_2_windowed(&mut self, a: CurveTarget, b: CurveTarget, scalar_a: &NonNativeTarget<Scalar>, scalar_b: &NonNativeTarget<Scalar>, window_bits: usize) -> CurveTarget {
+                let a_window = self.precompute_odd_window(a, window_bits);
+                let a_digits = self.recode_scalar_signed(scalar_a, window_bits);
 
-                let b_window = self.precompute_window(b, 4);
-                let b_four_bit_limbs = self.split_nonnative_to_4_bit_limbs(&scalar_b);
+                let b_window = self.precompute_odd_window(b, window_bits);
+                let b_digits = self.recode_scalar_signed(scalar_b, window_bits);
 
-                debug_assert!(a_four_bit_limbs.len() == b_four_bit_limbs.len());
+                debug_assert!(a_digits.len() == b_digits.len());
 
-                let num_limbs = a_four_bit_limbs.len();
-                let a_start = self.curve_random_access(a_four_bit_limbs[num_limbs - 1], &a_window);
-                let b_start = self.curve_random_access(b_four_bit_limbs[num_limbs - 1], &b_window);
+                let num_digits = a_digits.len();
+                let a_start = self.curve_signed_random_access(a_digits[num_digits - 1], &a_window);
+                let b_start = self.curve_signed_random_access(b_digits[num_digits - 1], &b_window);
                 let mut res = self.curve_add(a_start, b_start);
 
-                for (a_limb, b_limb) in a_four_bit_limbs.into_iter().zip(b_four_bit_limbs).rev().skip(1) {
-                    for _ in 0..4 {
+                for (&a_digit, &b_digit) in a_digits.iter().zip(b_digits.iter()).rev().skip(1) {
+                    for _ in 0..window_bits {
                         res = self.curve_double(res);
                     }
 
-                    let a_addend = self.curve_random_access(a_limb, &a_window);
-                    let b_addend = self.curve_random_access(b_limb, &b_window);
+                    let a_addend = self.curve_signed_random_access(a_digit, &a_window);
+                    let b_addend = self.curve_signed_random_access(b_digit, &b_window);
                     let addend = self.curve_add(a_addend, b_addend);
                     res = self.curve_add(res, addend);
                 }
 
                 res
             }
+
+            fn curve_verify_muladd(&mut self, q: CurveTarget, s: &NonNativeTarget<Scalar>, k: &NonNativeTarget<Scalar>, r: CurveTarget) {
+                // k = c0 / c1 mod n. We check (s * c1) * G + c0 * Q - c1 * R == 0
+                let c0 = add_virtual_signed_161_target(self);
+                let c1 = add_virtual_signed_161_target(self);
+                let k_biguint = self.nonnative_to_canonical_biguint(k);
+                self.add_simple_generator(LagrangeDecompositionGenerator {
+                    k: k_biguint,
+                    c0: c0.clone(),
+                    c1: c1.clone(),
+                });
+
+                curve_verify_muladd_split(self, q, s, k, r, &c0, &c1);
+            }
+
+            fn curve_map_to_curve(&mut self, u: QuinticExtensionTarget) -> CurveTarget {
+                let (a, b) = (WeierstrassPoint::A, WeierstrassPoint::B);
+
+                let u_squared = self.square_quintic_ext(u);
+                let z_u2 = self.mul_const_quintic_ext(SSWU_Z, u_squared);
+                let z_u2_squared = self.square_quintic_ext(z_u2);
+                let denom = self.add_quintic_ext(z_u2_squared, z_u2);
+                let one = self.one_quintic_ext();
+                let tv1 = self.div_or_zero_quintic_ext(one, denom);
+
+                let zero = self.zero_quintic_ext();
+                let is_exceptional = self.is_equal_quintic_ext(denom, zero);
+                let x1_exceptional = self.constant_quintic_ext(b / (SSWU_Z * a));
+                let one_plus_tv1 = self.add_const_quintic_ext(tv1, GFp5::ONE);
+                let x1 = self.mul_const_quintic_ext(-b / a, one_plus_tv1);
+                let x1 = self.select_quintic_ext(is_exceptional, x1_exceptional, x1);
+                let x2 = self.mul_quintic_ext(z_u2, x1);
+
+                let [gx1, gx2] = [x1, x2].map(|x| {
+                    let x_squared = self.square_quintic_ext(x);
+                    let x_squared_plus_a = self.add_const_quintic_ext(x_squared, a);
+                    let gx = self.mul_quintic_ext(x_squared_plus_a, x);
+                    self.add_const_quintic_ext(gx, b)
+                });
+
+                // exactly one of g(x1) and g(x2) is a square, so the prover can't choose the wrong one
+                let legendre = self.legendre_sym_quintic_ext(gx1);
+                let neg_one = self.neg_one();
+                let gx1_is_not_square = self.is_equal(legendre, neg_one);
+                let x = self.select_quintic_ext(gx1_is_not_square, x2, x1);
+                let gx = self.select_quintic_ext(gx1_is_not_square, gx2, gx1);
+
+                let y = self.any_sqrt_quintic_ext(gx);
+                let y_squared = self.square_quintic_ext(y);
+                self.connect_quintic_ext(y_squared, gx);
+
+                let u_sign = self.sgn0_quintic_ext(u);
+                let y_sign = self.sgn0_quintic_ext(y);
+                let signs_match = self.is_equal(u_sign.target, y_sign.target);
+                let neg_y = self.neg_quintic_ext(y);
+                let y = self.select_quintic_ext(signs_match, y, neg_y);
+
+                CurveTarget(([x, y], self._false()))
+            }
+
+            fn curve_hash_to_curve(&mut self, domain: &[u8], inputs: &[Target]) -> CurveTarget {
+                let mut message = hash_to_scalar_prefix(domain, inputs.len())
+                    .into_iter()
+                    .map(|x| self.constant(x))
+                    .collect::<Vec<_>>();
+                message.extend_from_slice(inputs);
+                let outputs = self.hash_n_to_m_no_pad::<PoseidonHash>(message, HASH_TO_CURVE_NUM_OUTPUTS);
+
+                let u0 = QuinticExtensionTarget::new([outputs[0], outputs[1], outputs[2], outputs[3], outputs[4]]);
+                let u1 = QuinticExtensionTarget::new([outputs[5], outputs[6], outputs[7], outputs[8], outputs[9]]);
+                let p0 = self.curve_map_to_curve(u0);
+                let p1 = self.curve_map_to_curve(u1);
+
+                // doubling maps the sum into the subgroup of order n
+                let sum = self.curve_add(p0, p1);
+                self.curve_double(sum)
+            }
+
+            fn curve_verify_nullifier(
+                &mut self,
+                pk: CurveTarget,
+                message: &[Target],
+                nullifier: CurveTarget,
+                c: &NonNativeTarget<Scalar>,
+                s: &NonNativeTarget<Scalar>,
+            ) {
+                let mut inputs = self.curve_encode_to_quintic_ext(pk).0.to_vec();
+                inputs.extend_from_slice(message);
+                let h = self.curve_hash_to_curve(NULLIFIER_HASH_DOMAIN, &inputs);
+
+                // r1 = s * G - c * pk, r2 = s * H - c * nullifier
+                let g = self.curve_generator();
+                let neg_pk = self.curve_neg(pk);
+                let neg_nullifier = self.curve_neg(nullifier);
+                let r1 = self.curve_muladd_2(g, neg_pk, s, c);
+                let r2 = self.curve_muladd_2(h, neg_nullifier, s, c);
+
+                let challenge_inputs = [pk, h, nullifier, r1, r2]
+                    .into_iter()
+                    .flat_map(|p| self.curve_encode_to_quintic_ext(p).0)
+                    .collect::<Vec<_>>();
+                let c_expected = self.hash_to_scalar(NULLIFIER_CHALLENGE_DOMAIN, &challenge_inputs);
+                self.connect_nonnative(c, &c_expected);
+            }
+
+            fn curve_verify_schnorr(&mut self, pk: CurveTarget, message: &[Target], s: &NonNativeTarget<Scalar>, e: &NonNativeTarget<Scalar>) {
+                // R = s * G + e * pk
+                let g = self.curve_generator();
+                let r = self.curve_muladd_2(g, pk, s, e);
+
+                let e_expected = curve_schnorr_challenge(self, r, pk, message);
+                self.connect_nonnative(e, &e_expected);
+            }
+
+            fn curve_verify_adaptor_pre_signature(
+                &mut self,
+                pk: CurveTarget,
+                t: CurveTarget,
+                message: &[Target],
+                s: &NonNativeTarget<Scalar>,
+                e: &NonNativeTarget<Scalar>,
+            ) {
+                // R = s * G + e * pk, and the challenge commits to R + T
+                let g = self.curve_generator();
+                let r = self.curve_muladd_2(g, pk, s, e);
+                let r_plus_t = self.curve_add(r, t);
+
+                let e_expected = curve_schnorr_challenge(self, r_plus_t, pk, message);
+                self.connect_nonnative(e, &e_expected);
+            }
         }
     };
 }
@@ -371,6 +810,203 @@ impl_circuit_builder_for_extension_degree!(2);
 impl_circuit_builder_for_extension_degree!(4);
 impl_circuit_builder_for_extension_degree!(5);
 
+// computes the same challenge as `schnorr::schnorr_challenge`
+fn curve_schnorr_challenge<const D: usize>(
+    builder: &mut CircuitBuilder<GFp, D>,
+    r: CurveTarget,
+    pk: CurveTarget,
+    message: &[Target],
+) -> NonNativeTarget<Scalar>
+where
+    GFp: Extendable<D>,
+    CircuitBuilder<GFp, D>: CircuitBuilderEcGFp5,
+{
+    let mut inputs = builder.curve_encode_to_quintic_ext(r).0.to_vec();
+    inputs.extend(builder.curve_encode_to_quintic_ext(pk).0);
+    inputs.extend_from_slice(message);
+    builder.hash_to_scalar(SCHNORR_CHALLENGE_DOMAIN, &inputs)
+}
+
+// the checks of `curve_verify_muladd`, given the Lagrange split k = c0 / c1 mod n
+fn curve_verify_muladd_split<const D: usize>(
+    builder: &mut CircuitBuilder<GFp, D>,
+    q: CurveTarget,
+    s: &NonNativeTarget<Scalar>,
+    k: &NonNativeTarget<Scalar>,
+    r: CurveTarget,
+    c0: &Signed161Target,
+    c1: &Signed161Target,
+) where
+    GFp: Extendable<D>,
+    CircuitBuilder<GFp, D>: CircuitBuilderEcGFp5,
+{
+    // c1 != 0, otherwise the check below passes trivially
+    let c1_abs_sum = builder.add_many(c1.abs_bits.iter().map(|b| b.target));
+    builder.inverse(c1_abs_sum);
+
+    // c0 == k * c1 mod n
+    let c0_scalar = signed_161_to_scalar(builder, c0);
+    let c1_scalar = signed_161_to_scalar(builder, c1);
+    let k_times_c1 = builder.scalar_mul(k, &c1_scalar);
+    builder.connect_nonnative(&c0_scalar, &k_times_c1);
+
+    // split t = s * c1 into two 160-bit halves, so t * G = t0 * G + t1 * (2^160 * G)
+    let t = builder.scalar_mul(s, &c1_scalar);
+    let t_bits = builder.split_nonnative_to_bits(&t);
+    let (t0_bits, t1_bits) = t_bits.split_at(160);
+
+    let g = Point::GENERATOR;
+    let g_160 = g * Scalar::from_noncanonical_biguint(BigUint::one() << 160);
+    let g_window = builder.precompute_odd_window_const(g, 4);
+    let g_160_window = builder.precompute_odd_window_const(g_160, 4);
+
+    // fold the signs of c0 and -c1 into Q and R
+    let q = builder.curve_conditional_neg(q, c0.is_neg);
+    let c1_is_pos = builder.not(c1.is_neg);
+    let r = builder.curve_conditional_neg(r, c1_is_pos);
+    let q_window = builder.precompute_odd_window(q, 4);
+    let r_window = builder.precompute_odd_window(r, 4);
+
+    let res = curve_multi_scalar_mul_odd_windows(
+        builder,
+        &[g_window, g_160_window, q_window, r_window],
+        &[t0_bits, t1_bits, &c0.abs_bits, &c1.abs_bits],
+        4,
+    );
+
+    let CurveTarget((_, is_inf)) = res;
+    builder.assert_one(is_inf.target);
+}
+
+// computes sum(scalars[i] * P_i), where odd_windows[i] = [P_i, 3P_i, ..., (2^window_bits - 1)P_i] and scalars[i] are
+// little-endian bits
+fn curve_multi_scalar_mul_odd_windows<const D: usize>(
+    builder: &mut CircuitBuilder<GFp, D>,
+    odd_windows: &[Vec<CurveTarget>],
+    scalars: &[&[BoolTarget]],
+    window_bits: usize,
+) -> CurveTarget
+where
+    GFp: Extendable<D>,
+    CircuitBuilder<GFp, D>: CircuitBuilderEcGFp5,
+{
+    // Odd signed digits only add up to odd numbers, so we recode x + 1 - x_0 instead of x, and subtract P at the end
+    // if x is even. With k digits and x < 2^(wk), the digits of t = (x >> 1) + 2^(wk - 1) give
+    // sum(e_i * 2^(wi)) = 2t - (2^(wk) - 1) = x + 1 - x_0 (see `signed_digits_from_bits`), so no witness is needed.
+    let num_bits = scalars.iter().map(|bits| bits.len()).max().unwrap();
+    let num_digits = num_bits.div_ceil(window_bits);
+    let digits = scalars
+        .iter()
+        .map(|bits| {
+            let mut t_bits = bits[1..].to_vec();
+            t_bits.resize(num_digits * window_bits - 1, builder._false());
+            t_bits.push(builder._true());
+            signed_digits_from_bits(builder, &t_bits, window_bits)
+        })
+        .collect::<Vec<_>>();
+
+    let mut res = builder.curve_zero();
+    for i in (0..num_digits).rev() {
+        if i != num_digits - 1 {
+            for _ in 0..window_bits {
+                res = builder.curve_double(res);
+            }
+        }
+
+        for (window, digits) in odd_windows.iter().zip(digits.iter()) {
+            let addend = builder.curve_signed_random_access(digits[i], window);
+            res = builder.curve_add(res, addend);
+        }
+    }
+
+    let zero = builder.curve_zero();
+    for (window, bits) in odd_windows.iter().zip(scalars.iter()) {
+        let neg_p = builder.curve_neg(window[0]);
+        let correction = builder.curve_select(bits[0], zero, neg_p);
+        res = builder.curve_add(res, correction);
+    }
+
+    res
+}
+
+// a signed integer in (-2^161, 2^161), as its sign and the little-endian bits of its absolute value
+#[derive(Clone, Debug)]
+struct Signed161Target {
+    is_neg: BoolTarget,
+    abs_bits: Vec<BoolTarget>,
+}
+
+fn add_virtual_signed_161_target<const D: usize>(builder: &mut CircuitBuilder<GFp, D>) -> Signed161Target
+where
+    GFp: Extendable<D>,
+{
+    Signed161Target {
+        is_neg: builder.add_virtual_bool_target_safe(),
+        abs_bits: (0..161).map(|_| builder.add_virtual_bool_target_safe()).collect(),
+    }
+}
+
+fn signed_161_to_scalar<const D: usize>(builder: &mut CircuitBuilder<GFp, D>, x: &Signed161Target) -> NonNativeTarget<Scalar>
+where
+    GFp: Extendable<D>,
+{
+    let abs_limbs = x.abs_bits.chunks(32).map(|bits| U32Target(builder.le_sum(bits.iter()))).collect();
+    let abs = BigUintTarget { limbs: abs_limbs };
+
+    // |x| < 2^161 < n, so n - |x| doesn't underflow
+    let modulus = builder.constant_biguint(&Scalar::order());
+    let neg = builder.sub_biguint(&modulus, &abs);
+
+    let zero = builder.zero();
+    let limbs = neg
+        .limbs
+        .iter()
+        .enumerate()
+        .map(|(i, neg_limb)| {
+            let abs_limb = abs.limbs.get(i).map_or(zero, |limb| limb.0);
+            U32Target(builder.select(x.is_neg, neg_limb.0, abs_limb))
+        })
+        .collect();
+
+    builder.biguint_to_nonnative(&BigUintTarget { limbs })
+}
+
+#[derive(Debug)]
+struct LagrangeDecompositionGenerator {
+    k: BigUintTarget,
+    c0: Signed161Target,
+    c1: Signed161Target,
+}
+
+impl LagrangeDecompositionGenerator {
+    fn set_signed_161_target(out_buffer: &mut GeneratedValues<GFp>, target: &Signed161Target, value: Signed161) {
+        let [l0, l1, l2] = value.to_u192();
+        let is_neg = (l2 >> 63) != 0;
+
+        let value = BigUint::from_slice(&[l0 as u32, (l0 >> 32) as u32, l1 as u32, (l1 >> 32) as u32, l2 as u32, (l2 >> 32) as u32]);
+        let abs = if is_neg { (BigUint::one() << 192) - value } else { value };
+
+        out_buffer.set_bool_target(target.is_neg, is_neg);
+        for (i, &bit) in target.abs_bits.iter().enumerate() {
+            out_buffer.set_bool_target(bit, abs.bit(i as u64));
+        }
+    }
+}
+
+impl SimpleGenerator<GFp> for LagrangeDecompositionGenerator {
+    fn dependencies(&self) -> Vec<Target> {
+        self.k.limbs.iter().map(|l| l.0).collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<GFp>, out_buffer: &mut GeneratedValues<GFp>) {
+        let k = Scalar::from_noncanonical_biguint(witness.get_biguint_target(self.k.clone()));
+        let (c0, c1) = k.lagrange();
+
+        Self::set_signed_161_target(out_buffer, &self.c0, c0);
+        Self::set_signed_161_target(out_buffer, &self.c1, c1);
+    }
+}
+
 
 pub trait PartialWitnessCurve<F: RichField + Extendable<5>>: Witness<F> {
     fn get_curve_target(&self, target: CurveTarget) -> WeierstrassPoint;
@@ -422,11 +1058,18 @@ impl<W: PartialWitnessQuinticExt<GFp>> PartialWitnessCurve<GFp> for W {
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
-    use plonky2::{field::types::Sample, plonk::{config::{PoseidonGoldilocksConfig, GenericConfig}, circuit_data::CircuitConfig}, iop::witness::PartialWitness};
+    use plonky2::{field::types::Sample, plonk::{config::{PoseidonGoldilocksConfig, GenericConfig}, circuit_data::{CircuitConfig, CircuitData}}, iop::witness::{PartialWitness, WitnessWrite}};
     use plonky2_ecdsa::gadgets::nonnative::CircuitBuilderNonNative;
-    use rand::thread_rng;
+    use rand::{thread_rng, Rng};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
 
+    use crate::curve::adaptor::adaptor_pre_sign;
+    use crate::curve::blind_schnorr::{BlindSignerSession, BlindUserSession};
     use crate::curve::curve::Point;
+    use crate::curve::hash_to_curve::{hash_to_curve, map_to_curve};
+    use crate::curve::nullifier::prove_nullifier;
+    use crate::curve::schnorr::schnorr_challenge;
+    use crate::gadgets::scalar_field::PartialWitnessScalar;
 
     use super::*;
 
@@ -487,7 +1130,7 @@ mod tests {
     }
 
     #[test]
-    fn test_curve_scalar_mul() -> Result<()> {
+    fn test_curve_sub() -> Result<()> {
         const D: usize = 2;
         type C = PoseidonGoldilocksConfig;
         type F = <C as GenericConfig<D>>::F;
@@ -497,27 +1140,30 @@ mod tests {
         let config = CircuitConfig::standard_recursion_config();
         let mut builder = CircuitBuilder::<F, D>::new(config);
 
-        let p = Point::sample(&mut rng);
-        let s = Scalar::sample(&mut rng);
-        let prod_expected = p * s;
+        let p1 = Point::sample(&mut rng);
+        let p2 = Point::sample(&mut rng);
+        let p3_expected = p1 - p2;
 
-        let p = builder.curve_constant(p.to_weierstrass());
-        let s = builder.constant_nonnative(s);
+        let p1 = builder.curve_constant(p1.to_weierstrass());
+        let p2 = builder.curve_constant(p2.to_weierstrass());
+        let p3 = builder.curve_sub(p1, p2);
+        builder.register_curve_public_input(p3);
+
+        // a - a should be the neutral point
+        let CurveTarget((_, is_inf)) = builder.curve_sub(p1, p1);
+        builder.assert_one(is_inf.target);
 
-        let prod = builder.curve_scalar_mul(p, &s);
-        builder.register_curve_public_input(prod);
-        
         let circuit = builder.build::<C>();
 
         let mut pw = PartialWitness::new();
-        pw.set_curve_target(prod, prod_expected.to_weierstrass());
+        pw.set_curve_target(p3, p3_expected.to_weierstrass());
 
         let proof = circuit.prove(pw)?;
         circuit.verify(proof)
     }
 
     #[test]
-    fn test_curve_scalar_mul_const() -> Result<()> {
+    fn test_curve_conditional_neg() -> Result<()> {
         const D: usize = 2;
         type C = PoseidonGoldilocksConfig;
         type F = <C as GenericConfig<D>>::F;
@@ -528,25 +1174,27 @@ mod tests {
         let mut builder = CircuitBuilder::<F, D>::new(config);
 
         let p = Point::sample(&mut rng);
-        let s = Scalar::sample(&mut rng);
-        let prod_expected = p * s;
+        let p_target = builder.curve_constant(p.to_weierstrass());
 
-        let s = builder.constant_nonnative(s);
+        let true_target = builder.constant_bool(true);
+        let false_target = builder.constant_bool(false);
+        let neg_p = builder.curve_conditional_neg(p_target, true_target);
+        let same_p = builder.curve_conditional_neg(p_target, false_target);
+        builder.register_curve_public_input(neg_p);
+        builder.register_curve_public_input(same_p);
 
-        let prod = builder.curve_scalar_mul_const(p, &s);
-        builder.register_curve_public_input(prod);
-        
         let circuit = builder.build::<C>();
 
         let mut pw = PartialWitness::new();
-        pw.set_curve_target(prod, prod_expected.to_weierstrass());
+        pw.set_curve_target(neg_p, (-p).to_weierstrass());
+        pw.set_curve_target(same_p, p.to_weierstrass());
 
         let proof = circuit.prove(pw)?;
         circuit.verify(proof)
     }
 
     #[test]
-    fn test_curve_encode() -> Result<()> {
+    fn test_curve_scalar_mul_edge_cases() -> Result<()> {
         const D: usize = 2;
         type C = PoseidonGoldilocksConfig;
         type F = <C as GenericConfig<D>>::F;
@@ -557,23 +1205,34 @@ mod tests {
         let mut builder = CircuitBuilder::<F, D>::new(config);
 
         let p = Point::sample(&mut rng);
-        let w_expected = p.encode();
-        
-        let p = builder.curve_constant(p.to_weierstrass());
-        let w = builder.curve_encode_to_quintic_ext(p);
-        builder.register_quintic_ext_public_input(w);
+        let p_target = builder.curve_constant(p.to_weierstrass());
+
+        // coordinates of the neutral point are unconstrained, so only check the flag
+        let zero = builder.zero_nonnative();
+        let CurveTarget((_, is_inf)) = builder.curve_scalar_mul(p_target, &zero);
+        builder.assert_one(is_inf.target);
+
+        let mut prods = Vec::new();
+        for s in [Scalar::ONE, Scalar::NEG_ONE] {
+            let s_target = builder.constant_nonnative(s);
+            let prod = builder.curve_scalar_mul(p_target, &s_target);
+            builder.register_curve_public_input(prod);
+            prods.push((prod, p * s));
+        }
 
         let circuit = builder.build::<C>();
 
         let mut pw = PartialWitness::new();
-        pw.set_quintic_ext_target(w, w_expected);
+        for (prod, prod_expected) in prods {
+            pw.set_curve_target(prod, prod_expected.to_weierstrass());
+        }
 
         let proof = circuit.prove(pw)?;
         circuit.verify(proof)
     }
 
     #[test]
-    fn test_curve_decode() -> Result<()> {
+    fn test_curve_scalar_mul() -> Result<()> {
         const D: usize = 2;
         type C = PoseidonGoldilocksConfig;
         type F = <C as GenericConfig<D>>::F;
@@ -583,24 +1242,27 @@ mod tests {
         let config = CircuitConfig::standard_recursion_config();
         let mut builder = CircuitBuilder::<F, D>::new(config);
 
-        let p_expected = Point::sample(&mut rng);
-        let w = p_expected.encode();
+        let p = Point::sample(&mut rng);
+        let s = Scalar::sample(&mut rng);
+        let prod_expected = p * s;
 
-        let w = builder.constant_quintic_ext(w);
-        let p = builder.curve_decode_from_quintic_ext(w);
-        builder.register_curve_public_input(p);
+        let p = builder.curve_constant(p.to_weierstrass());
+        let s = builder.constant_nonnative(s);
 
+        let prod = builder.curve_scalar_mul(p, &s);
+        builder.register_curve_public_input(prod);
+        
         let circuit = builder.build::<C>();
 
         let mut pw = PartialWitness::new();
-        pw.set_curve_target(p, p_expected.to_weierstrass());
+        pw.set_curve_target(prod, prod_expected.to_weierstrass());
 
         let proof = circuit.prove(pw)?;
         circuit.verify(proof)
     }
 
     #[test]
-    fn test_curve_muladd_2() -> Result<()> {
+    fn test_curve_scalar_mul_windowed() -> Result<()> {
         const D: usize = 2;
         type C = PoseidonGoldilocksConfig;
         type F = <C as GenericConfig<D>>::F;
@@ -610,25 +1272,622 @@ mod tests {
         let config = CircuitConfig::standard_recursion_config();
         let mut builder = CircuitBuilder::<F, D>::new(config);
 
-        let p1 = Point::sample(&mut rng);
-        let p2 = Point::sample(&mut rng);
-        let s1 = Scalar::sample(&mut rng);
-        let s2 = Scalar::sample(&mut rng);
-        let prod_expected = p1 * s1 + p2 * s2;
+        let p = Point::sample(&mut rng);
+        let s = Scalar::sample(&mut rng);
+        let prod_expected = p * s;
 
-        let p1 = builder.curve_constant(p1.to_weierstrass());
-        let s1 = builder.constant_nonnative(s1);
+        let p_target = builder.curve_constant(p.to_weierstrass());
+        let s = builder.constant_nonnative(s);
 
-        let p2 = builder.curve_constant(p2.to_weierstrass());
-        let s2 = builder.constant_nonnative(s2);
+        let prod_3 = builder.curve_scalar_mul_windowed(p_target, &s, 3);
+        let prod_5 = builder.curve_scalar_mul_windowed(p_target, &s, 5);
+        let prod_const_6 = builder.curve_scalar_mul_const_windowed(p, &s, 6);
+        builder.register_curve_public_input(prod_3);
+        builder.register_curve_public_input(prod_5);
+        builder.register_curve_public_input(prod_const_6);
 
-        let prod = builder.curve_muladd_2(p1, p2, &s1, &s2);
-        builder.register_curve_public_input(prod);
-        
         let circuit = builder.build::<C>();
 
         let mut pw = PartialWitness::new();
-        pw.set_curve_target(prod, prod_expected.to_weierstrass());
+        pw.set_curve_target(prod_3, prod_expected.to_weierstrass());
+        pw.set_curve_target(prod_5, prod_expected.to_weierstrass());
+        pw.set_curve_target(prod_const_6, prod_expected.to_weierstrass());
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_scalar_mul_short() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p = Point::sample(&mut rng);
+        let s_64 = rng.gen::<u64>();
+        let s_128 = rng.gen::<u128>();
+        let prod_64_expected = p * Scalar::from_canonical_u64(s_64);
+        let prod_128_expected = p * Scalar::from_noncanonical_u128(s_128);
+
+        let p = builder.curve_constant(p.to_weierstrass());
+        let limbs_64 = [(); 2].map(|_| U32Target(builder.add_virtual_target()));
+        let limbs_128 = [(); 4].map(|_| U32Target(builder.add_virtual_target()));
+
+        let prod_64 = builder.curve_scalar_mul_u64(p, limbs_64);
+        let prod_128 = builder.curve_scalar_mul_u128(p, limbs_128);
+        builder.register_curve_public_input(prod_64);
+        builder.register_curve_public_input(prod_128);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (i, limb) in limbs_64.iter().enumerate() {
+            pw.set_target(limb.0, F::from_canonical_u32((s_64 >> (32 * i)) as u32));
+        }
+        for (i, limb) in limbs_128.iter().enumerate() {
+            pw.set_target(limb.0, F::from_canonical_u32((s_128 >> (32 * i)) as u32));
+        }
+        pw.set_curve_target(prod_64, prod_64_expected.to_weierstrass());
+        pw.set_curve_target(prod_128, prod_128_expected.to_weierstrass());
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_verify_muladd() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let q = Point::sample(&mut rng);
+        let s = Scalar::sample(&mut rng);
+        let k = Scalar::sample(&mut rng);
+        let r = Point::GENERATOR * s + q * k;
+        assert!(q.verify_muladd_vartime(s, k, r));
+
+        let q_target = builder.add_virtual_curve_target();
+        let r_target = builder.add_virtual_curve_target();
+        let s = builder.constant_nonnative(s);
+        let k = builder.constant_nonnative(k);
+        builder.curve_verify_muladd(q_target, &s, &k, r_target);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_curve_target(q_target, q.to_weierstrass());
+        pw.set_curve_target(r_target, r.to_weierstrass());
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    // An invalid witness makes the witness generation panic when it breaks a copy constraint or divides by zero.
+    // Otherwise, the prover still runs, but the proof doesn't verify.
+    fn prove_fails<C: GenericConfig<D, F = GFp>, const D: usize>(circuit: &CircuitData<GFp, C, D>, pw: PartialWitness<GFp>) -> bool
+    where
+        GFp: Extendable<D>,
+    {
+        match catch_unwind(AssertUnwindSafe(|| circuit.prove(pw))) {
+            Ok(Ok(proof)) => circuit.verify(proof).is_err(),
+            _ => true,
+        }
+    }
+
+    #[test]
+    fn test_curve_verify_muladd_rejects() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let q = Point::sample(&mut rng);
+        let s = Scalar::sample(&mut rng);
+        let k = Scalar::sample(&mut rng);
+        let r = Point::GENERATOR * s + q * k;
+
+        let q_target = builder.add_virtual_curve_target();
+        let r_target = builder.add_virtual_curve_target();
+        let s_target = builder.constant_nonnative(s);
+        let k_target = builder.add_virtual_scalar_target();
+        builder.curve_verify_muladd(q_target, &s_target, &k_target, r_target);
+        let k_limbs = builder.nonnative_to_canonical_biguint(&k_target);
+
+        let circuit = builder.build::<C>();
+
+        let witness = |r: Point, k: Scalar| {
+            let mut pw = PartialWitness::new();
+            pw.set_curve_target(q_target, q.to_weierstrass());
+            pw.set_curve_target(r_target, r.to_weierstrass());
+            pw.set_scalar_target(&k_limbs, k);
+            pw
+        };
+
+        // wrong r, wrong k
+        assert!(prove_fails(&circuit, witness(r + Point::GENERATOR, k)));
+        assert!(prove_fails(&circuit, witness(r, k + Scalar::ONE)));
+
+        let proof = circuit.prove(witness(r, k))?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_verify_muladd_rejects_zero_c1() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // with c0 = c1 = 0, c0 == k * c1 and c1 * s * G + c0 * Q - c1 * R == 0 hold for any s, k, Q and R,
+        // so only the c1 != 0 check rejects it
+        let q_target = builder.curve_constant(Point::sample(&mut rng).to_weierstrass());
+        let r_target = builder.curve_constant(Point::sample(&mut rng).to_weierstrass());
+        let s_target = builder.constant_nonnative(Scalar::sample(&mut rng));
+        let k_target = builder.constant_nonnative(Scalar::sample(&mut rng));
+        let c0 = add_virtual_signed_161_target(&mut builder);
+        let c1 = add_virtual_signed_161_target(&mut builder);
+        curve_verify_muladd_split(&mut builder, q_target, &s_target, &k_target, r_target, &c0, &c1);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for c in [&c0, &c1] {
+            pw.set_bool_target(c.is_neg, false);
+            for &bit in c.abs_bits.iter() {
+                pw.set_bool_target(bit, false);
+            }
+        }
+        assert!(prove_fails(&circuit, pw));
+    }
+
+    #[test]
+    fn test_curve_add_double_unchecked() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p1 = Point::sample(&mut rng);
+        let p2 = Point::sample(&mut rng);
+        let sum_expected = p1 + p2;
+        let double_expected = p1.double();
+
+        let p1 = builder.curve_constant(p1.to_weierstrass());
+        let p2 = builder.curve_constant(p2.to_weierstrass());
+        let sum = builder.curve_add_unchecked(p1, p2);
+        let double = builder.curve_double_unchecked(p1);
+        builder.register_curve_public_input(sum);
+        builder.register_curve_public_input(double);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_curve_target(sum, sum_expected.to_weierstrass());
+        pw.set_curve_target(double, double_expected.to_weierstrass());
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_scalar_mul_incomplete() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p = Point::sample(&mut rng);
+        let p_target = builder.curve_constant(p.to_weierstrass());
+        let inf = builder.curve_zero();
+
+        let mut prods = Vec::new();
+        for s in [Scalar::sample(&mut rng), Scalar::ONE, Scalar::NEG_ONE] {
+            let s_target = builder.constant_nonnative(s);
+            let prod = builder.curve_scalar_mul_incomplete(p_target, &s_target);
+            builder.register_curve_public_input(prod);
+            prods.push((prod, p * s));
+        }
+        for window_bits in [2, 3, 5] {
+            let s = Scalar::sample(&mut rng);
+            let s_target = builder.constant_nonnative(s);
+            let prod = builder.curve_scalar_mul_incomplete_windowed(p_target, &s_target, window_bits);
+            builder.register_curve_public_input(prod);
+            prods.push((prod, p * s));
+        }
+
+        let zero = builder.zero_nonnative();
+        let CurveTarget((_, is_inf)) = builder.curve_scalar_mul_incomplete(p_target, &zero);
+        builder.assert_one(is_inf.target);
+
+        let s = builder.constant_nonnative(Scalar::sample(&mut rng));
+        let CurveTarget((_, is_inf)) = builder.curve_scalar_mul_incomplete(inf, &s);
+        builder.assert_one(is_inf.target);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (prod, prod_expected) in prods {
+            pw.set_curve_target(prod, prod_expected.to_weierstrass());
+        }
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_scalar_mul_const() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p = Point::sample(&mut rng);
+        let s = Scalar::sample(&mut rng);
+        let prod_expected = p * s;
+
+        let s = builder.constant_nonnative(s);
+
+        let prod = builder.curve_scalar_mul_const(p, &s);
+        builder.register_curve_public_input(prod);
+        
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_curve_target(prod, prod_expected.to_weierstrass());
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_encode() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p = Point::sample(&mut rng);
+        let w_expected = p.encode();
+        
+        let p = builder.curve_constant(p.to_weierstrass());
+        let w = builder.curve_encode_to_quintic_ext(p);
+        builder.register_quintic_ext_public_input(w);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_quintic_ext_target(w, w_expected);
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_decode() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p_expected = Point::sample(&mut rng);
+        let w = p_expected.encode();
+
+        let w = builder.constant_quintic_ext(w);
+        let p = builder.curve_decode_from_quintic_ext(w);
+        builder.register_curve_public_input(p);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_curve_target(p, p_expected.to_weierstrass());
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_schnorr_challenge() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // e = H(R || pk || m), including R = 0, which encodes to 0
+        let pk_expected = Point::sample(&mut rng);
+        let m_expected = [F::sample(&mut rng), F::sample(&mut rng)];
+
+        let pk = builder.add_virtual_curve_target();
+        let m = builder.add_virtual_targets(2);
+        let mut rs = Vec::new();
+        for r_expected in [Point::sample(&mut rng), Point::NEUTRAL] {
+            let r = builder.add_virtual_curve_target();
+            let e = curve_schnorr_challenge(&mut builder, r, pk, &m);
+            let e_expected = builder.constant_nonnative(schnorr_challenge(r_expected, pk_expected, &m_expected));
+            builder.connect_nonnative(&e, &e_expected);
+            rs.push((r, r_expected));
+        }
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_curve_target(pk, pk_expected.to_weierstrass());
+        pw.set_target(m[0], m_expected[0]);
+        pw.set_target(m[1], m_expected[1]);
+        for (r, r_expected) in rs {
+            pw.set_curve_target(r, r_expected.to_weierstrass());
+        }
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_muladd_2() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p1 = Point::sample(&mut rng);
+        let p2 = Point::sample(&mut rng);
+        let s1 = Scalar::sample(&mut rng);
+        let s2 = Scalar::sample(&mut rng);
+        let prod_expected = p1 * s1 + p2 * s2;
+
+        let p1 = builder.curve_constant(p1.to_weierstrass());
+        let s1 = builder.constant_nonnative(s1);
+
+        let p2 = builder.curve_constant(p2.to_weierstrass());
+        let s2 = builder.constant_nonnative(s2);
+
+        let prod = builder.curve_muladd_2(p1, p2, &s1, &s2);
+        builder.register_curve_public_input(prod);
+        
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_curve_target(prod, prod_expected.to_weierstrass());
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_map_to_curve() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // about half of these map outside of the subgroup of order n
+        let us = (0..8).map(|_| GFp5::sample(&mut rng)).collect::<Vec<_>>();
+        let mut doubled = Vec::new();
+        for &u in us.iter() {
+            let u = builder.constant_quintic_ext(u);
+            let p = builder.curve_map_to_curve(u);
+            let p_doubled = builder.curve_double(p);
+            builder.register_curve_public_input(p_doubled);
+            doubled.push(p_doubled);
+        }
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&p, &u) in doubled.iter().zip(us.iter()) {
+            pw.set_curve_target(p, map_to_curve(u).double().to_weierstrass());
+        }
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_hash_to_curve() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let inputs_expected = [F::sample(&mut rng), F::sample(&mut rng), F::sample(&mut rng)];
+        let p_expected = hash_to_curve(b"test", &inputs_expected);
+
+        let inputs = builder.add_virtual_targets(3);
+        let p = builder.curve_hash_to_curve(b"test", &inputs);
+        builder.register_curve_public_input(p);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&t, &v) in inputs.iter().zip(inputs_expected.iter()) {
+            pw.set_target(t, v);
+        }
+        pw.set_curve_target(p, p_expected.to_weierstrass());
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_verify_nullifier() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let sk = Scalar::sample(&mut rng);
+        let pk_expected = Point::GENERATOR * sk;
+        let message_expected = [F::sample(&mut rng), F::sample(&mut rng)];
+        let (nullifier_expected, proof_expected) = prove_nullifier(sk, &message_expected);
+
+        // the key is public here; to keep it private, e.g. in anonymous voting, prove instead that it belongs to
+        // a public set of voter keys, and only reveal the nullifier
+        let pk = builder.add_virtual_curve_target();
+        let message = builder.add_virtual_targets(2);
+        let nullifier = builder.add_virtual_curve_target();
+        let c = builder.add_virtual_scalar_target();
+        let s = builder.add_virtual_scalar_target();
+        builder.curve_verify_nullifier(pk, &message, nullifier, &c, &s);
+        builder.register_curve_public_input(pk);
+        builder.register_public_inputs(&message);
+        builder.register_curve_public_input(nullifier);
+
+        let c = builder.nonnative_to_canonical_biguint(&c);
+        let s = builder.nonnative_to_canonical_biguint(&s);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_curve_target(pk, pk_expected.to_weierstrass());
+        pw.set_target(message[0], message_expected[0]);
+        pw.set_target(message[1], message_expected[1]);
+        pw.set_curve_target(nullifier, nullifier_expected.to_weierstrass());
+        pw.set_scalar_target(&c, proof_expected.c);
+        pw.set_scalar_target(&s, proof_expected.s);
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_verify_blind_schnorr() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // a token issued blindly, redeemed by proving knowledge of a signature of its (private) serial number
+        let sk = Scalar::sample(&mut rng);
+        let pk_expected = Point::GENERATOR * sk;
+        let serial_expected = [F::sample(&mut rng), F::sample(&mut rng)];
+
+        let (signer, commitment) = BlindSignerSession::new(&mut rng);
+        let (user, challenge) = BlindUserSession::new(pk_expected, commitment, &serial_expected, &mut rng);
+        let response = signer.respond(sk, &challenge, &mut rng);
+        let sig_expected = user.unblind(&challenge, &response).unwrap();
+
+        let pk = builder.add_virtual_curve_target();
+        let serial = builder.add_virtual_targets(2);
+        let s = builder.add_virtual_scalar_target();
+        let e = builder.add_virtual_scalar_target();
+        builder.curve_verify_schnorr(pk, &serial, &s, &e);
+        builder.register_curve_public_input(pk);
+
+        let s = builder.nonnative_to_canonical_biguint(&s);
+        let e = builder.nonnative_to_canonical_biguint(&e);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_curve_target(pk, pk_expected.to_weierstrass());
+        pw.set_target(serial[0], serial_expected[0]);
+        pw.set_target(serial[1], serial_expected[1]);
+        pw.set_scalar_target(&s, sig_expected.s);
+        pw.set_scalar_target(&e, sig_expected.e);
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_curve_verify_adaptor_pre_signature() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let sk = Scalar::sample(&mut rng);
+        let pk_expected = Point::GENERATOR * sk;
+        let t_expected = Point::GENERATOR * Scalar::sample(&mut rng);
+        let message_expected = [F::sample(&mut rng), F::sample(&mut rng)];
+        let pre_sig_expected = adaptor_pre_sign(sk, t_expected, &message_expected);
+
+        let pk = builder.add_virtual_curve_target();
+        let t = builder.add_virtual_curve_target();
+        let message = builder.add_virtual_targets(2);
+        let s = builder.add_virtual_scalar_target();
+        let e = builder.add_virtual_scalar_target();
+        builder.curve_verify_adaptor_pre_signature(pk, t, &message, &s, &e);
+        builder.register_curve_public_input(pk);
+        builder.register_curve_public_input(t);
+
+        let s = builder.nonnative_to_canonical_biguint(&s);
+        let e = builder.nonnative_to_canonical_biguint(&e);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_curve_target(pk, pk_expected.to_weierstrass());
+        pw.set_curve_target(t, t_expected.to_weierstrass());
+        pw.set_target(message[0], message_expected[0]);
+        pw.set_target(message[1], message_expected[1]);
+        pw.set_scalar_target(&s, pre_sig_expected.s);
+        pw.set_scalar_target(&e, pre_sig_expected.e);
 
         let proof = circuit.prove(pw)?;
         circuit.verify(proof)
@@ -0,0 +1,140 @@
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::Witness;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::curve::curve::Point;
+use crate::curve::hd::{ExtendedPublicKey, CHAIN_CODE_LEN, CHILD_CHAIN_CODE_DOMAIN, CHILD_TWEAK_DOMAIN};
+use crate::curve::GFp;
+use crate::gadgets::base_field::PartialWitnessQuinticExt;
+use crate::gadgets::curve::{CircuitBuilderEcGFp5, CurveTarget, PartialWitnessCurve};
+use crate::gadgets::scalar_field::{hash_with_prefix, CircuitBuilderScalar};
+
+pub type ChainCodeTarget = [Target; CHAIN_CODE_LEN];
+
+#[derive(Clone, Copy, Debug)]
+pub struct ExtendedPublicKeyTarget {
+    pub pk: CurveTarget,
+    pub chain_code: ChainCodeTarget,
+}
+
+pub trait CircuitBuilderHd {
+    fn add_virtual_extended_public_key_target(&mut self) -> ExtendedPublicKeyTarget;
+
+    /// Derives the non-hardened child of `parent` at `index`, like `ExtendedPublicKey::derive_child`.
+    /// `index` is constrained to be below `HARDENED_OFFSET`.
+    fn hd_derive_public_child(&mut self, parent: ExtendedPublicKeyTarget, index: Target) -> ExtendedPublicKeyTarget;
+
+    /// Derives the non-hardened descendant of `parent` at the path `indices`, like `ExtendedPublicKey::derive_path`.
+    fn hd_derive_public_path(&mut self, parent: ExtendedPublicKeyTarget, indices: &[Target]) -> ExtendedPublicKeyTarget;
+}
+
+macro_rules! impl_circuit_builder_for_extension_degree {
+    ($degree:literal) => {
+        impl CircuitBuilderHd for CircuitBuilder<GFp, $degree> {
+            fn add_virtual_extended_public_key_target(&mut self) -> ExtendedPublicKeyTarget {
+                ExtendedPublicKeyTarget {
+                    pk: self.add_virtual_curve_target(),
+                    chain_code: self.add_virtual_target_arr(),
+                }
+            }
+
+            fn hd_derive_public_child(&mut self, parent: ExtendedPublicKeyTarget, index: Target) -> ExtendedPublicKeyTarget {
+                self.range_check(index, 31);
+
+                let mut inputs = parent.chain_code.to_vec();
+                inputs.extend(self.curve_encode_to_quintic_ext(parent.pk).0);
+                inputs.push(index);
+
+                let tweak = self.hash_to_scalar(CHILD_TWEAK_DOMAIN, &inputs);
+                let tweak_times_g = self.curve_scalar_mul_const(Point::GENERATOR, &tweak);
+                let pk = self.curve_add(parent.pk, tweak_times_g);
+
+                // same as `hd::hash_to_chain_code`
+                let outputs = hash_with_prefix(self, CHILD_CHAIN_CODE_DOMAIN, &inputs, CHAIN_CODE_LEN);
+                let chain_code = core::array::from_fn(|i| outputs[i]);
+
+                ExtendedPublicKeyTarget { pk, chain_code }
+            }
+
+            fn hd_derive_public_path(&mut self, parent: ExtendedPublicKeyTarget, indices: &[Target]) -> ExtendedPublicKeyTarget {
+                indices.iter().fold(parent, |key, &index| self.hd_derive_public_child(key, index))
+            }
+        }
+    };
+}
+
+impl_circuit_builder_for_extension_degree!(1);
+impl_circuit_builder_for_extension_degree!(2);
+impl_circuit_builder_for_extension_degree!(4);
+impl_circuit_builder_for_extension_degree!(5);
+
+pub trait PartialWitnessHd: Witness<GFp> {
+    fn get_extended_public_key_target(&self, target: ExtendedPublicKeyTarget) -> ExtendedPublicKey;
+    fn set_extended_public_key_target(&mut self, target: ExtendedPublicKeyTarget, value: ExtendedPublicKey);
+}
+
+impl<W: PartialWitnessQuinticExt<GFp>> PartialWitnessHd for W {
+    fn get_extended_public_key_target(&self, target: ExtendedPublicKeyTarget) -> ExtendedPublicKey {
+        let pk = self.get_curve_target(target.pk);
+        ExtendedPublicKey {
+            pk: Point::decode(pk.encode()).expect("curve target is not a valid point"),
+            chain_code: target.chain_code.map(|t| self.get_target(t)),
+        }
+    }
+
+    fn set_extended_public_key_target(&mut self, target: ExtendedPublicKeyTarget, value: ExtendedPublicKey) {
+        self.set_curve_target(target.pk, value.pk.to_weierstrass());
+        for (&t, &c) in target.chain_code.iter().zip(value.chain_code.iter()) {
+            self.set_target(t, c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::types::Field;
+    use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::curve::hd::{DerivationPath, ExtendedSecretKey};
+
+    #[test]
+    fn test_hd_derive_public_path() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+        let seed: [u8; 32] = rng.gen();
+        let master = ExtendedSecretKey::from_seed(&seed).public_key();
+        let path = DerivationPath(vec![rng.gen_range(0..1 << 31), 1]);
+        let child_expected = master.derive_path(&path).unwrap();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let master_target = builder.add_virtual_extended_public_key_target();
+        let indices = builder.add_virtual_targets(path.0.len());
+        let child = builder.hd_derive_public_path(master_target, &indices);
+
+        // e.g. prove that a public address is a child of a secret master key
+        builder.register_curve_public_input(child.pk);
+        builder.register_public_inputs(&child.chain_code);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_extended_public_key_target(master_target, master);
+        for (&t, &index) in indices.iter().zip(path.0.iter()) {
+            pw.set_target(t, F::from_canonical_u32(index));
+        }
+        pw.set_extended_public_key_target(child, child_expected);
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+}
@@ -1,3 +1,9 @@
 pub mod base_field;
 pub mod scalar_field;
 pub mod curve;
+pub mod polynomial;
+pub mod credential;
+pub mod hd;
+pub mod ring;
+pub mod sigma;
+pub mod stealth;
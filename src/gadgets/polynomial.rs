@@ -0,0 +1,238 @@
+use alloc::vec::Vec;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::curve::polynomial::lagrange_basis;
+use crate::curve::{GFp, GFp5};
+use crate::gadgets::base_field::{CircuitBuilderGFp5, QuinticExtensionTarget};
+
+/// A polynomial over GFp5, represented by its coefficients, lowest degree first
+#[derive(Clone, Debug)]
+pub struct PolynomialQuinticExtTarget(pub Vec<QuinticExtensionTarget>);
+
+impl PolynomialQuinticExtTarget {
+    pub fn new(coeffs: Vec<QuinticExtensionTarget>) -> Self {
+        Self(coeffs)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+pub trait CircuitBuilderPolynomialQuinticExt {
+    fn add_virtual_polynomial_quintic_ext_target(&mut self, num_coeffs: usize) -> PolynomialQuinticExtTarget;
+    fn constant_polynomial_quintic_ext(&mut self, coeffs: &[GFp5]) -> PolynomialQuinticExtTarget;
+
+    /// evaluates `poly` at `x` using Horner's rule
+    fn eval_polynomial_quintic_ext(
+        &mut self,
+        poly: &PolynomialQuinticExtTarget,
+        x: QuinticExtensionTarget,
+    ) -> QuinticExtensionTarget;
+    fn add_polynomial_quintic_ext(
+        &mut self,
+        a: &PolynomialQuinticExtTarget,
+        b: &PolynomialQuinticExtTarget,
+    ) -> PolynomialQuinticExtTarget;
+    fn mul_polynomial_quintic_ext(
+        &mut self,
+        a: &PolynomialQuinticExtTarget,
+        b: &PolynomialQuinticExtTarget,
+    ) -> PolynomialQuinticExtTarget;
+
+    /// returns the unique polynomial of degree less than `xs.len()` such that p(xs[i]) = ys[i].
+    /// Since the points are constant, the Lagrange basis is computed outside of the circuit.
+    fn interpolate_polynomial_quintic_ext(
+        &mut self,
+        xs: &[GFp5],
+        ys: &[QuinticExtensionTarget],
+    ) -> PolynomialQuinticExtTarget;
+}
+
+macro_rules! impl_circuit_builder_for_extension_degree {
+    ($degree:literal) => {
+        impl CircuitBuilderPolynomialQuinticExt for CircuitBuilder<GFp, $degree> {
+            fn add_virtual_polynomial_quintic_ext_target(&mut self, num_coeffs: usize) -> PolynomialQuinticExtTarget {
+                PolynomialQuinticExtTarget((0..num_coeffs).map(|_| self.add_virtual_quintic_ext_target()).collect())
+            }
+
+            fn constant_polynomial_quintic_ext(&mut self, coeffs: &[GFp5]) -> PolynomialQuinticExtTarget {
+                PolynomialQuinticExtTarget(coeffs.iter().map(|&c| self.constant_quintic_ext(c)).collect())
+            }
+
+            fn eval_polynomial_quintic_ext(
+                &mut self,
+                poly: &PolynomialQuinticExtTarget,
+                x: QuinticExtensionTarget,
+            ) -> QuinticExtensionTarget {
+                let mut coeffs = poly.0.iter().rev();
+                let mut res = match coeffs.next() {
+                    Some(&c) => c,
+                    None => return self.zero_quintic_ext(),
+                };
+                for &c in coeffs {
+                    let res_times_x = self.mul_quintic_ext(res, x);
+                    res = self.add_quintic_ext(res_times_x, c);
+                }
+
+                res
+            }
+
+            fn add_polynomial_quintic_ext(
+                &mut self,
+                a: &PolynomialQuinticExtTarget,
+                b: &PolynomialQuinticExtTarget,
+            ) -> PolynomialQuinticExtTarget {
+                let (longer, shorter) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+                let mut coeffs = longer.0.clone();
+                for (c, &s) in coeffs.iter_mut().zip(shorter.0.iter()) {
+                    *c = self.add_quintic_ext(*c, s);
+                }
+
+                PolynomialQuinticExtTarget(coeffs)
+            }
+
+            fn mul_polynomial_quintic_ext(
+                &mut self,
+                a: &PolynomialQuinticExtTarget,
+                b: &PolynomialQuinticExtTarget,
+            ) -> PolynomialQuinticExtTarget {
+                if a.is_empty() || b.is_empty() {
+                    return PolynomialQuinticExtTarget(Vec::new());
+                }
+
+                let mut terms = vec![Vec::new(); a.len() + b.len() - 1];
+                for (i, &a_i) in a.0.iter().enumerate() {
+                    for (j, &b_j) in b.0.iter().enumerate() {
+                        terms[i + j].push(self.mul_quintic_ext(a_i, b_j));
+                    }
+                }
+
+                PolynomialQuinticExtTarget(terms.into_iter().map(|terms| self.add_many_quintic_ext(terms)).collect())
+            }
+
+            fn interpolate_polynomial_quintic_ext(
+                &mut self,
+                xs: &[GFp5],
+                ys: &[QuinticExtensionTarget],
+            ) -> PolynomialQuinticExtTarget {
+                assert_eq!(xs.len(), ys.len());
+
+                let basis = lagrange_basis(xs);
+                let coeffs = (0..xs.len())
+                    .map(|k| {
+                        let terms = basis
+                            .iter()
+                            .zip(ys.iter())
+                            .map(|(basis_poly, &y)| self.mul_const_quintic_ext(basis_poly[k], y))
+                            .collect();
+                        self.add_many_quintic_ext(terms)
+                    })
+                    .collect();
+
+                PolynomialQuinticExtTarget(coeffs)
+            }
+        }
+    };
+}
+
+impl_circuit_builder_for_extension_degree!(1);
+impl_circuit_builder_for_extension_degree!(2);
+impl_circuit_builder_for_extension_degree!(4);
+impl_circuit_builder_for_extension_degree!(5);
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::curve::polynomial::{poly_add, poly_eval, poly_mul};
+    use crate::gadgets::base_field::PartialWitnessQuinticExt;
+
+    #[test]
+    fn test_polynomial_arithmetic() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a_expected = (0..3).map(|_| GFp5::sample(&mut rng)).collect::<Vec<_>>();
+        let b_expected = (0..5).map(|_| GFp5::sample(&mut rng)).collect::<Vec<_>>();
+        let x_expected = GFp5::sample(&mut rng);
+
+        let a = builder.add_virtual_polynomial_quintic_ext_target(3);
+        let b = builder.constant_polynomial_quintic_ext(&b_expected);
+        let x = builder.constant_quintic_ext(x_expected);
+
+        let sum = builder.add_polynomial_quintic_ext(&a, &b);
+        let prod = builder.mul_polynomial_quintic_ext(&a, &b);
+        let a_at_x = builder.eval_polynomial_quintic_ext(&a, x);
+
+        let sum_expected = builder.constant_polynomial_quintic_ext(&poly_add(&a_expected, &b_expected));
+        let prod_expected = builder.constant_polynomial_quintic_ext(&poly_mul(&a_expected, &b_expected));
+        let a_at_x_expected = builder.constant_quintic_ext(poly_eval(&a_expected, x_expected));
+        for (&c, &c_expected) in sum.0.iter().zip(sum_expected.0.iter()).chain(prod.0.iter().zip(prod_expected.0.iter())) {
+            builder.connect_quintic_ext(c, c_expected);
+        }
+        builder.connect_quintic_ext(a_at_x, a_at_x_expected);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_quintic_ext_targets(&a.0, &a_expected);
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_interpolate_polynomial() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let coeffs_expected = (0..4).map(|_| GFp5::sample(&mut rng)).collect::<Vec<_>>();
+        let xs = (1..=4).map(GFp5::from_canonical_u64).collect::<Vec<_>>();
+        let ys_expected = xs.iter().map(|&x| poly_eval(&coeffs_expected, x)).collect::<Vec<_>>();
+
+        let ys = ys_expected.iter().map(|_| builder.add_virtual_quintic_ext_target()).collect::<Vec<_>>();
+        let poly = builder.interpolate_polynomial_quintic_ext(&xs, &ys);
+
+        // e.g. Shamir reconstruction: the secret is p(0)
+        builder.register_quintic_ext_public_input(poly.0[0]);
+        for &c in poly.0.iter() {
+            builder.register_quintic_ext_public_input(c);
+        }
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_quintic_ext_targets(&ys, &ys_expected);
+
+        let proof = circuit.prove(pw)?;
+        assert_eq!(
+            proof.public_inputs,
+            [&coeffs_expected[..1], &coeffs_expected[..]].concat().iter().flat_map(|c| c.0).collect::<Vec<_>>()
+        );
+        circuit.verify(proof)
+    }
+}
@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::Witness;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2_ecdsa::gadgets::biguint::BigUintTarget;
+use plonky2_ecdsa::gadgets::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+
+use crate::curve::ring::{RingSignature, KEY_IMAGE_DOMAIN, RING_CHALLENGE_DOMAIN, RING_DIGEST_DOMAIN};
+use crate::curve::scalar_field::{hash_to_scalar_prefix, Scalar};
+use crate::curve::GFp;
+use crate::gadgets::curve::{CircuitBuilderEcGFp5, CurveTarget, PartialWitnessCurve};
+use crate::gadgets::scalar_field::{CircuitBuilderScalar, PartialWitnessScalar};
+
+/// In-circuit counterpart of `ring::RingSignature`. Created with `add_virtual_ring_signature_target`.
+#[derive(Clone, Debug)]
+pub struct RingSignatureTarget {
+    pub key_image: CurveTarget,
+    pub c0: NonNativeTarget<Scalar>,
+    pub responses: Vec<NonNativeTarget<Scalar>>,
+    // the limbs of c0 and of every response, for the witness
+    limbs: Vec<BigUintTarget>,
+}
+
+pub trait CircuitBuilderRing {
+    fn add_virtual_ring_signature_target(&mut self, ring_size: usize) -> RingSignatureTarget;
+
+    /// Verifies a linkable ring signature of `message` by a member of `ring`, like `ring::ring_verify`.
+    /// The cost is linear in the size of the ring: a hash to the curve, two `curve_muladd_2` and
+    /// a challenge hash per member.
+    fn verify_ring_signature(&mut self, ring: &[CurveTarget], message: &[Target], signature: &RingSignatureTarget);
+}
+
+macro_rules! impl_circuit_builder_for_extension_degree {
+    ($degree:literal) => {
+        impl CircuitBuilderRing for CircuitBuilder<GFp, $degree> {
+            fn add_virtual_ring_signature_target(&mut self, ring_size: usize) -> RingSignatureTarget {
+                let key_image = self.add_virtual_curve_target();
+                let c0 = self.add_virtual_scalar_target();
+                let responses = (0..ring_size).map(|_| self.add_virtual_scalar_target()).collect::<Vec<_>>();
+                let limbs = [&c0].into_iter().chain(responses.iter()).map(|x| self.nonnative_to_canonical_biguint(x)).collect();
+
+                RingSignatureTarget { key_image, c0, responses, limbs }
+            }
+
+            fn verify_ring_signature(&mut self, ring: &[CurveTarget], message: &[Target], signature: &RingSignatureTarget) {
+                assert!(!ring.is_empty());
+                assert_eq!(signature.responses.len(), ring.len());
+
+                let encoded_ring = ring
+                    .iter()
+                    .map(|&pk| self.curve_encode_to_quintic_ext(pk).0)
+                    .collect::<Vec<_>>();
+
+                let mut digest_message = hash_to_scalar_prefix(RING_DIGEST_DOMAIN, 5 * ring.len())
+                    .into_iter()
+                    .map(|x| self.constant(x))
+                    .collect::<Vec<_>>();
+                digest_message.extend(encoded_ring.iter().flatten());
+                let digest = self.hash_n_to_m_no_pad::<PoseidonHash>(digest_message, 4);
+
+                let key_image = signature.key_image;
+                let key_image_encoded = self.curve_encode_to_quintic_ext(key_image);
+                let g = self.curve_generator();
+
+                let mut c = signature.c0.clone();
+                for ((&pk, encoded_pk), s) in ring.iter().zip(encoded_ring.iter()).zip(signature.responses.iter()) {
+                    // L = s * G + c * P, R = s * H(P) + c * I
+                    let h = self.curve_hash_to_curve(KEY_IMAGE_DOMAIN, encoded_pk);
+                    let l = self.curve_muladd_2(g, pk, s, &c);
+                    let r = self.curve_muladd_2(h, key_image, s, &c);
+
+                    let mut inputs = digest.clone();
+                    inputs.extend(key_image_encoded.0);
+                    inputs.extend_from_slice(message);
+                    inputs.extend(self.curve_encode_to_quintic_ext(l).0);
+                    inputs.extend(self.curve_encode_to_quintic_ext(r).0);
+                    c = self.hash_to_scalar(RING_CHALLENGE_DOMAIN, &inputs);
+                }
+                self.connect_nonnative(&signature.c0, &c);
+            }
+        }
+    };
+}
+
+impl_circuit_builder_for_extension_degree!(1);
+impl_circuit_builder_for_extension_degree!(2);
+impl_circuit_builder_for_extension_degree!(4);
+impl_circuit_builder_for_extension_degree!(5);
+
+pub trait PartialWitnessRing: Witness<GFp> {
+    fn set_ring_signature_target(&mut self, target: &RingSignatureTarget, value: &RingSignature);
+}
+
+impl<W: Witness<GFp>> PartialWitnessRing for W {
+    fn set_ring_signature_target(&mut self, target: &RingSignatureTarget, value: &RingSignature) {
+        assert_eq!(target.responses.len(), value.responses.len());
+
+        self.set_curve_target(target.key_image, value.key_image.to_weierstrass());
+        let values = [value.c0].into_iter().chain(value.responses.iter().copied());
+        for (limbs, x) in target.limbs.iter().zip(values) {
+            self.set_scalar_target(limbs, x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::types::Sample;
+    use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::curve::curve::Point;
+    use crate::curve::ring::ring_sign;
+
+    #[test]
+    fn test_verify_ring_signature() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let sks = (0..3).map(|_| Scalar::sample(&mut rng)).collect::<Vec<_>>();
+        let ring_values = sks.iter().map(|&sk| Point::GENERATOR * sk).collect::<Vec<_>>();
+        let message_values = [GFp::sample(&mut rng), GFp::sample(&mut rng)];
+        let signature_value = ring_sign(&ring_values, 1, sks[1], &message_values, &mut rng);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let ring = (0..ring_values.len()).map(|_| builder.add_virtual_curve_target()).collect::<Vec<_>>();
+        let message = builder.add_virtual_target_arr::<2>();
+        let signature = builder.add_virtual_ring_signature_target(ring.len());
+        for &pk in ring.iter() {
+            builder.register_curve_public_input(pk);
+        }
+        builder.register_public_inputs(&message);
+        builder.register_curve_public_input(signature.key_image);
+
+        builder.verify_ring_signature(&ring, &message, &signature);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&t, pk) in ring.iter().zip(ring_values.iter()) {
+            pw.set_curve_target(t, pk.to_weierstrass());
+        }
+        pw.set_target_arr(message, message_values);
+        pw.set_ring_signature_target(&signature, &signature_value);
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+}
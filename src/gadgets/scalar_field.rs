@@ -1,13 +1,59 @@
-use num::BigUint;
-use plonky2::{hash::hash_types::RichField, plonk::circuit_builder::CircuitBuilder, iop::witness::{PartialWitness, WitnessWrite, Witness}};
-use plonky2_ecdsa::gadgets::biguint::BigUintTarget;
-use plonky2_field::{extension::Extendable, types::{Field, PrimeField}};
+use num::{BigUint, Integer, One};
+use plonky2::{hash::{hash_types::RichField, poseidon::PoseidonHash}, plonk::circuit_builder::CircuitBuilder, iop::{witness::{PartitionWitness, WitnessWrite, Witness}, target::{BoolTarget, Target}, generator::{GeneratedValues, SimpleGenerator}}};
+use plonky2_ecdsa::gadgets::{biguint::{BigUintTarget, CircuitBuilderBiguint, GeneratedValuesBigUint, WitnessBigUint}, nonnative::{CircuitBuilderNonNative, NonNativeTarget}};
+use plonky2_field::{extension::Extendable, types::{Field, Field64, PrimeField, PrimeField64}};
+use plonky2_u32::gadgets::{arithmetic_u32::{CircuitBuilderU32, U32Target}, range_check::range_check_u32_circuit};
 
-use crate::curve::scalar_field::Scalar;
+use crate::curve::GFp;
+use crate::curve::scalar_field::{hash_to_scalar_prefix, Scalar, HASH_TO_SCALAR_NUM_OUTPUTS};
 
+// max number of u32 limbs range-checked by a single `U32RangeCheckGate`, chosen so that it fits in the standard config
+const RANGE_CHECK_LIMBS_PER_GATE: usize = 5;
 
+/// A signed, odd digit of a recoded scalar. Its value is `(-1)^is_neg * (2 * index + 1)`,
+/// so `index` can be used directly to look up a window of odd multiples of a point.
+#[derive(Copy, Clone, Debug)]
+pub struct SignedDigitTarget {
+	pub index: Target,
+	pub is_neg: BoolTarget,
+}
+
+/// Scalars are represented as `NonNativeTarget<Scalar>`. Unless stated otherwise, scalar arguments are
+/// assumed to be canonical (i.e. less than n), and returned scalars are constrained to be canonical.
 pub trait CircuitBuilderScalar<F: RichField + Extendable<D>, const D: usize> {
 	fn register_scalar_public_input(&mut self, scalar: &BigUintTarget);
+
+	/// Returns a new scalar target, constrained to be canonical.
+	fn add_virtual_scalar_target(&mut self) -> NonNativeTarget<Scalar>;
+	fn scalar_constant(&mut self, c: Scalar) -> NonNativeTarget<Scalar>;
+
+	/// Recodes `scalar` into `ceil(320 / window_bits)` signed digits e_i such that
+	/// `scalar = sum(e_i * 2^(window_bits * i)) mod n`, least significant digit first.
+	/// Every digit is odd and lies in [-(2^window_bits - 1), 2^window_bits - 1].
+	fn recode_scalar_signed(&mut self, scalar: &NonNativeTarget<Scalar>, window_bits: usize) -> Vec<SignedDigitTarget>;
+
+	/// Multiplies two scalars. Unlike `mul_nonnative`, this only uses range-check gates that fit in
+	/// `standard_recursion_config`, and the result is constrained to be canonical.
+	fn scalar_mul(&mut self, a: &NonNativeTarget<Scalar>, b: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar>;
+	fn scalar_add(&mut self, a: &NonNativeTarget<Scalar>, b: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar>;
+	fn scalar_sub(&mut self, a: &NonNativeTarget<Scalar>, b: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar>;
+	fn scalar_neg(&mut self, a: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar>;
+
+	/// Returns the inverse of `a`, which must be nonzero: otherwise, there is no valid witness.
+	fn scalar_inverse(&mut self, a: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar>;
+
+	fn scalar_is_equal(&mut self, a: &NonNativeTarget<Scalar>, b: &NonNativeTarget<Scalar>) -> BoolTarget;
+
+	/// Returns `a` if `b` is true, and `c` otherwise.
+	fn scalar_select(&mut self, b: BoolTarget, a: &NonNativeTarget<Scalar>, c: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar>;
+
+	/// Returns the integer whose little-endian bits are `bits`, reduced mod n. Any number of bits is accepted.
+	fn scalar_from_bits(&mut self, bits: &[BoolTarget]) -> NonNativeTarget<Scalar>;
+
+	/// Hashes `inputs` to a scalar with Poseidon, under the domain separation tag `domain`.
+	/// This computes exactly the same function as `curve::scalar_field::hash_to_scalar`,
+	/// and the result is constrained to be canonical.
+	fn hash_to_scalar(&mut self, domain: &[u8], inputs: &[Target]) -> NonNativeTarget<Scalar>;
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderScalar<F, D> for CircuitBuilder<F, D> {
@@ -16,29 +62,536 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderScalar<F, D> fo
 			self.register_public_input(limb.0);
 		}
 	}
+
+	fn add_virtual_scalar_target(&mut self) -> NonNativeTarget<Scalar> {
+		let scalar = self.add_virtual_nonnative_target::<Scalar>();
+		let scalar_biguint = self.nonnative_to_canonical_biguint(&scalar);
+
+		// scalar <= n - 1. This also range-checks its limbs
+		let modulus_minus_one = self.constant_biguint(&(Scalar::order() - BigUint::one()));
+		let is_canonical = self.cmp_biguint(&scalar_biguint, &modulus_minus_one);
+		self.assert_one(is_canonical.target);
+
+		scalar
+	}
+
+	fn scalar_constant(&mut self, c: Scalar) -> NonNativeTarget<Scalar> {
+		self.constant_nonnative(c)
+	}
+
+	fn recode_scalar_signed(&mut self, scalar: &NonNativeTarget<Scalar>, window_bits: usize) -> Vec<SignedDigitTarget> {
+		debug_assert!(window_bits > 1);
+		let num_digits = Scalar::BITS.div_ceil(window_bits);
+
+		// With d_i the w-bit digits of some t, sum((2d_i - (2^w - 1)) * 2^(wi)) = 2t - (2^(wk) - 1),
+		// where k is the number of digits. So we witness t = (scalar + 2^(wk) - 1) / 2 mod n,
+		// and take e_i = 2d_i - (2^w - 1).
+		let t = self.add_virtual_nonnative_target::<Scalar>();
+		let scalar_biguint = self.nonnative_to_canonical_biguint(scalar);
+		let t_biguint = self.nonnative_to_canonical_biguint(&t);
+		self.add_simple_generator(SignedDigitsGenerator {
+			scalar: scalar_biguint,
+			t: t_biguint,
+			num_bits: window_bits * num_digits,
+		});
+
+		// check 2t == scalar + 2^(wk) - 1 mod n
+		let offset = self.constant_nonnative(signed_digits_offset(window_bits * num_digits));
+		let two_t = self.scalar_add(&t, &t);
+		let scalar_plus_offset = self.scalar_add(scalar, &offset);
+		self.connect_nonnative(&two_t, &scalar_plus_offset);
+
+		// splitting t into bits also range-checks its limbs
+		let mut bits = self.split_nonnative_to_bits(&t);
+		bits.resize(window_bits * num_digits, self._false());
+
+		signed_digits_from_bits(self, &bits, window_bits)
+	}
+
+	fn scalar_mul(&mut self, a: &NonNativeTarget<Scalar>, b: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar> {
+		let a = self.nonnative_to_canonical_biguint(a);
+		let b = self.nonnative_to_canonical_biguint(b);
+		let prod = self.mul_biguint(&a, &b);
+
+		// a, b < n, so the quotient is < n
+		reduce_biguint(self, &prod, a.num_limbs())
+	}
+
+	fn scalar_add(&mut self, a: &NonNativeTarget<Scalar>, b: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar> {
+		let a = self.nonnative_to_canonical_biguint(a);
+		let b = self.nonnative_to_canonical_biguint(b);
+		let sum = self.add_biguint(&a, &b);
+
+		reduce_biguint(self, &sum, 1)
+	}
+
+	fn scalar_sub(&mut self, a: &NonNativeTarget<Scalar>, b: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar> {
+		// a + (n - b) is in (0, 2n), and n - b does not underflow since b is canonical
+		let a = self.nonnative_to_canonical_biguint(a);
+		let b = self.nonnative_to_canonical_biguint(b);
+		let modulus = self.constant_biguint(&Scalar::order());
+		let neg_b = self.sub_biguint(&modulus, &b);
+		let diff = self.add_biguint(&a, &neg_b);
+
+		reduce_biguint(self, &diff, 1)
+	}
+
+	fn scalar_neg(&mut self, a: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar> {
+		let a = self.nonnative_to_canonical_biguint(a);
+		let modulus = self.constant_biguint(&Scalar::order());
+		let neg_a = self.sub_biguint(&modulus, &a);
+
+		// n - a is in (0, n], so we still need to reduce it
+		reduce_biguint(self, &neg_a, 1)
+	}
+
+	fn scalar_inverse(&mut self, a: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar> {
+		let inv = self.add_virtual_scalar_target();
+		let a_biguint = self.nonnative_to_canonical_biguint(a);
+		let inv_biguint = self.nonnative_to_canonical_biguint(&inv);
+		self.add_simple_generator(ScalarInverseGenerator {
+			a: a_biguint,
+			inv: inv_biguint,
+		});
+
+		// a * inv == 1, which cannot hold if a == 0
+		let prod = self.scalar_mul(a, &inv);
+		let one = self.constant_nonnative(Scalar::ONE);
+		self.connect_nonnative(&prod, &one);
+
+		inv
+	}
+
+	fn scalar_is_equal(&mut self, a: &NonNativeTarget<Scalar>, b: &NonNativeTarget<Scalar>) -> BoolTarget {
+		let a = scalar_limbs(self, a);
+		let b = scalar_limbs(self, b);
+
+		let mut res = self._true();
+		for (a_limb, b_limb) in a.into_iter().zip(b) {
+			let limb_is_equal = self.is_equal(a_limb.0, b_limb.0);
+			res = self.and(res, limb_is_equal);
+		}
+
+		res
+	}
+
+	fn scalar_select(&mut self, b: BoolTarget, a: &NonNativeTarget<Scalar>, c: &NonNativeTarget<Scalar>) -> NonNativeTarget<Scalar> {
+		let a = scalar_limbs(self, a);
+		let c = scalar_limbs(self, c);
+
+		let limbs = a.into_iter().zip(c).map(|(a_limb, c_limb)| U32Target(self.select(b, a_limb.0, c_limb.0))).collect();
+		self.biguint_to_nonnative(&BigUintTarget { limbs })
+	}
+
+	fn scalar_from_bits(&mut self, bits: &[BoolTarget]) -> NonNativeTarget<Scalar> {
+		let limbs = bits.chunks(32).map(|limb_bits| U32Target(self.le_sum(limb_bits.iter()))).collect();
+		let value = BigUintTarget { limbs };
+
+		// n > 2^318, so anything shorter is already canonical
+		if bits.len() <= 318 {
+			return self.biguint_to_nonnative(&value);
+		}
+
+		let quotient_limbs = (bits.len() - 318).div_ceil(32);
+		reduce_biguint(self, &value, quotient_limbs)
+	}
+
+	fn hash_to_scalar(&mut self, domain: &[u8], inputs: &[Target]) -> NonNativeTarget<Scalar> {
+		let outputs = hash_with_prefix(self, domain, inputs, HASH_TO_SCALAR_NUM_OUTPUTS);
+
+		// The native function reduces sum(x_i * p^i) mod n. We compute sum(x_i * (p^i mod n)) instead,
+		// which is congruent but much smaller: it is below 10 * 2^64 * n, so the quotient fits in 3 limbs.
+		let p = Scalar::from_canonical_u64(GFp::ORDER);
+		let mut p_i = Scalar::ONE;
+		let mut sum = self.constant_biguint(&BigUint::default());
+		for &x in outputs.iter() {
+			let x = split_canonical_u64(self, x);
+			let c = self.constant_biguint(&p_i.to_canonical_biguint());
+			let term = self.mul_biguint(&x, &c);
+			sum = self.add_biguint(&sum, &term);
+			p_i *= p;
+		}
+
+		reduce_biguint(self, &sum, 3)
+	}
+}
+
+// In-circuit counterpart of `scalar_field::hash_with_prefix`, with the same framing as the `hash_to_scalar` gadget.
+pub(crate) fn hash_with_prefix<F: RichField + Extendable<D>, const D: usize>(
+	builder: &mut CircuitBuilder<F, D>,
+	domain: &[u8],
+	inputs: &[Target],
+	num_outputs: usize,
+) -> Vec<Target> {
+	let mut message = hash_to_scalar_prefix(domain, inputs.len())
+		.into_iter()
+		.map(|x| builder.constant(F::from_canonical_u64(x.to_canonical_u64())))
+		.collect::<Vec<_>>();
+	message.extend_from_slice(inputs);
+	builder.hash_n_to_m_no_pad::<PoseidonHash>(message, num_outputs)
+}
+
+// Returns the limbs of `x`, padded with zeros to the full number of limbs of a scalar.
+fn scalar_limbs<F: RichField + Extendable<D>, const D: usize>(builder: &mut CircuitBuilder<F, D>, x: &NonNativeTarget<Scalar>) -> Vec<U32Target> {
+	let mut limbs = builder.nonnative_to_canonical_biguint(x).limbs;
+	limbs.resize_with(Scalar::BITS / 32, || builder.zero_u32());
+	limbs
+}
+
+// Turns the `window_bits`-bit digits d_i of some t, given by its little-endian `bits`, into the odd signed digits
+// e_i = 2d_i - (2^w - 1), so that sum(e_i * 2^(wi)) = 2t - (2^(wk) - 1) for k digits.
+pub(crate) fn signed_digits_from_bits<F: RichField + Extendable<D>, const D: usize>(
+	builder: &mut CircuitBuilder<F, D>,
+	bits: &[BoolTarget],
+	window_bits: usize,
+) -> Vec<SignedDigitTarget> {
+	debug_assert!(bits.len().is_multiple_of(window_bits));
+	let half_window_max = builder.constant(F::from_canonical_u64((1 << (window_bits - 1)) - 1));
+	bits
+		.chunks(window_bits)
+		.map(|digit_bits| {
+			// if the top bit is set, e_i = 2 * low + 1. Otherwise, e_i = -(2 * (2^(w-1) - 1 - low) + 1).
+			let top = digit_bits[window_bits - 1];
+			let low = builder.le_sum(digit_bits[..window_bits - 1].iter());
+			let complement = builder.sub(half_window_max, low);
+			let diff = builder.sub(low, complement);
+			let index = builder.mul_add(top.target, diff, complement);
+
+			SignedDigitTarget { index, is_neg: builder.not(top) }
+		})
+		.collect()
+}
+
+// Splits a field element into two u32 limbs, checking that they are its canonical representation.
+fn split_canonical_u64<F: RichField + Extendable<D>, const D: usize>(builder: &mut CircuitBuilder<F, D>, x: Target) -> BigUintTarget {
+	let (lo, hi) = builder.split_low_high(x, 32, 64);
+
+	// lo + 2^32 * hi is either x or x + p. In the latter case, hi == 2^32 - 1 and lo != 0.
+	let hi_max = builder.constant(F::from_canonical_u32(u32::MAX));
+	let hi_is_max = builder.is_equal(hi, hi_max);
+	let lo_if_hi_is_max = builder.mul(hi_is_max.target, lo);
+	builder.assert_zero(lo_if_hi_is_max);
+
+	BigUintTarget { limbs: vec![U32Target(lo), U32Target(hi)] }
+}
+
+// Reduces `x` mod n. The quotient floor(x / n) must fit in `quotient_limbs` limbs.
+// Only uses range-check gates that fit in `standard_recursion_config`, and the result is canonical.
+fn reduce_biguint<F: RichField + Extendable<D>, const D: usize>(
+	builder: &mut CircuitBuilder<F, D>,
+	x: &BigUintTarget,
+	quotient_limbs: usize,
+) -> NonNativeTarget<Scalar> {
+	let modulus = builder.constant_biguint(&Scalar::order());
+	let rem = builder.add_virtual_nonnative_target::<Scalar>();
+	let rem_biguint = builder.nonnative_to_canonical_biguint(&rem);
+	let quotient = builder.add_virtual_biguint_target(quotient_limbs);
+
+	builder.add_simple_generator(ScalarReduceGenerator {
+		x: x.clone(),
+		rem: rem_biguint.clone(),
+		quotient: quotient.clone(),
+	});
+
+	for limbs in quotient.limbs.chunks(RANGE_CHECK_LIMBS_PER_GATE) {
+		range_check_u32_circuit(builder, limbs.to_vec());
+	}
+
+	// x == rem + n * quotient
+	let mod_times_quotient = builder.mul_biguint(&modulus, &quotient);
+	let x_actual = builder.add_biguint(&rem_biguint, &mod_times_quotient);
+	builder.connect_biguint(x, &x_actual);
+
+	// rem <= n - 1. This also range-checks the limbs of rem
+	let modulus_minus_one = builder.constant_biguint(&(Scalar::order() - BigUint::one()));
+	let is_canonical = builder.cmp_biguint(&rem_biguint, &modulus_minus_one);
+	builder.assert_one(is_canonical.target);
+
+	rem
+}
+
+// (2^num_bits - 1) mod n
+fn signed_digits_offset(num_bits: usize) -> Scalar {
+	Scalar::from_noncanonical_biguint((BigUint::one() << num_bits) - BigUint::one())
+}
+
+#[derive(Debug)]
+struct SignedDigitsGenerator {
+	scalar: BigUintTarget,
+	t: BigUintTarget,
+	num_bits: usize,
+}
+
+impl<F: RichField> SimpleGenerator<F> for SignedDigitsGenerator {
+	fn dependencies(&self) -> Vec<Target> {
+		self.scalar.limbs.iter().map(|l| l.0).collect()
+	}
+
+	fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+		let scalar = witness.get_scalar_target(&self.scalar);
+		let t = (scalar + signed_digits_offset(self.num_bits)) / Scalar::TWO;
+
+		out_buffer.set_scalar_target(&self.t, t);
+	}
+}
+
+#[derive(Debug)]
+struct ScalarReduceGenerator {
+	x: BigUintTarget,
+	rem: BigUintTarget,
+	quotient: BigUintTarget,
+}
+
+impl<F: RichField> SimpleGenerator<F> for ScalarReduceGenerator {
+	fn dependencies(&self) -> Vec<Target> {
+		self.x.limbs.iter().map(|l| l.0).collect()
+	}
+
+	fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+		let x = witness.get_biguint_target(self.x.clone());
+		let (quotient, rem) = x.div_rem(&Scalar::order());
+
+		out_buffer.set_biguint_target(&self.rem, &rem);
+		out_buffer.set_biguint_target(&self.quotient, &quotient);
+	}
+}
+
+#[derive(Debug)]
+struct ScalarInverseGenerator {
+	a: BigUintTarget,
+	inv: BigUintTarget,
+}
+
+impl<F: RichField> SimpleGenerator<F> for ScalarInverseGenerator {
+	fn dependencies(&self) -> Vec<Target> {
+		self.a.limbs.iter().map(|l| l.0).collect()
+	}
+
+	fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+		let a = witness.get_scalar_target(&self.a);
+
+		out_buffer.set_scalar_target(&self.inv, a.invert());
+	}
+}
+
+/// Witness accessors for scalars, for any `Witness`: a `PartialWitness` when proving, or the
+/// `PartitionWitness` that generators read from. Scalars are accessed through their limbs, as returned by
+/// `nonnative_to_canonical_biguint`, since `NonNativeTarget` does not expose them outside of a builder.
+pub trait PartialWitnessScalar<F: RichField>: Witness<F> {
+	fn get_scalar_target(&self, target: &BigUintTarget) -> Scalar;
+	fn set_scalar_target(&mut self, target: &BigUintTarget, value: Scalar);
+}
+
+impl<W: Witness<F>, F: RichField> PartialWitnessScalar<F> for W {
+	fn get_scalar_target(&self, target: &BigUintTarget) -> Scalar {
+		let limbs = target.limbs.iter().map(|limb| self.get_target(limb.0).to_canonical_u64() as u32).collect::<Vec<_>>();
+		Scalar::from_noncanonical_biguint(BigUint::from_slice(&limbs))
+	}
+
+	fn set_scalar_target(&mut self, target: &BigUintTarget, value: Scalar) {
+		for (&limb, limb_value) in target.limbs.iter().zip(scalar_limb_values(value, target.num_limbs())) {
+			self.set_target(limb.0, F::from_canonical_u32(limb_value));
+		}
+	}
 }
 
-pub trait PartialWitnessScalar<F: RichField> {
+/// Lets generators write scalars to their `GeneratedValues`. Use `PartialWitnessScalar` to read them.
+pub trait GeneratedValuesScalar<F: RichField> {
 	fn set_scalar_target(&mut self, target: &BigUintTarget, value: Scalar);
-	fn get_scalar_target(&mut self, target: &BigUintTarget) -> Scalar;
 }
 
-impl<F: RichField> PartialWitnessScalar<F> for PartialWitness<F> {
+impl<F: RichField> GeneratedValuesScalar<F> for GeneratedValues<F> {
 	fn set_scalar_target(&mut self, target: &BigUintTarget, value: Scalar) {
-		let value = value.to_canonical_biguint();
-		for (&limb, limb_value) in target.limbs.iter().zip(value.to_u32_digits()) {
+		for (&limb, limb_value) in target.limbs.iter().zip(scalar_limb_values(value, target.num_limbs())) {
 			self.set_target(limb.0, F::from_canonical_u32(limb_value));
 		}
 	}
+}
 
-	fn get_scalar_target(&mut self, target: &BigUintTarget) -> Scalar {
-		let mut limbs = Vec::new();
-		for limb in target.limbs.iter() {
-			limbs.push(self.get_target(limb.0).to_canonical_u64() as u32);
+// the canonical u32 limbs of `value`, padded with zeros to `num_limbs`
+fn scalar_limb_values(value: Scalar, num_limbs: usize) -> Vec<u32> {
+	let mut limbs = value.to_canonical_biguint().to_u32_digits();
+	assert!(limbs.len() <= num_limbs, "scalar does not fit in the target's limbs");
+	limbs.resize(num_limbs, 0);
+	limbs
+}
+
+#[cfg(test)]
+mod tests {
+	use anyhow::Result;
+	use plonky2::{field::types::Sample, plonk::{config::{PoseidonGoldilocksConfig, GenericConfig}, circuit_data::CircuitConfig}, iop::witness::PartialWitness};
+	use rand::{thread_rng, Rng};
+
+	use super::*;
+	use crate::curve::scalar_field::hash_to_scalar;
+
+	#[test]
+	fn test_scalar_mul() -> Result<()> {
+		const D: usize = 2;
+		type C = PoseidonGoldilocksConfig;
+		type F = <C as GenericConfig<D>>::F;
+
+		let mut rng = thread_rng();
+
+		let config = CircuitConfig::standard_recursion_config();
+		let mut builder = CircuitBuilder::<F, D>::new(config);
+
+		let a = Scalar::sample(&mut rng);
+		let b = Scalar::sample(&mut rng);
+		let prod_expected = a * b;
+
+		let a = builder.constant_nonnative(a);
+		let b = builder.constant_nonnative(b);
+		let prod = builder.scalar_mul(&a, &b);
+		let prod_expected = builder.constant_nonnative(prod_expected);
+		builder.connect_nonnative(&prod, &prod_expected);
+
+		let circuit = builder.build::<C>();
+		let proof = circuit.prove(PartialWitness::new())?;
+		circuit.verify(proof)
+	}
+
+	#[test]
+	fn test_scalar_arithmetic() -> Result<()> {
+		const D: usize = 2;
+		type C = PoseidonGoldilocksConfig;
+		type F = <C as GenericConfig<D>>::F;
+
+		let mut rng = thread_rng();
+
+		let config = CircuitConfig::standard_recursion_config();
+		let mut builder = CircuitBuilder::<F, D>::new(config);
+
+		let a_value = Scalar::sample(&mut rng);
+		let b_value = Scalar::sample(&mut rng);
+
+		let a = builder.add_virtual_scalar_target();
+		let b = builder.add_virtual_scalar_target();
+		let a_biguint = builder.nonnative_to_canonical_biguint(&a);
+		let b_biguint = builder.nonnative_to_canonical_biguint(&b);
+		let zero = builder.scalar_constant(Scalar::ZERO);
+
+		let expected = [
+			(builder.scalar_add(&a, &b), a_value + b_value),
+			(builder.scalar_sub(&a, &b), a_value - b_value),
+			(builder.scalar_sub(&a, &a), Scalar::ZERO),
+			(builder.scalar_neg(&a), -a_value),
+			(builder.scalar_neg(&zero), Scalar::ZERO),
+			(builder.scalar_inverse(&b), b_value.invert()),
+		];
+		for (x, x_value) in expected {
+			let x_expected = builder.scalar_constant(x_value);
+			builder.connect_nonnative(&x, &x_expected);
 		}
 
-		let as_biguint = BigUint::from_slice(&limbs);
-		Scalar::from_noncanonical_biguint(as_biguint)
+		let circuit = builder.build::<C>();
+
+		let mut pw = PartialWitness::new();
+		pw.set_scalar_target(&a_biguint, a_value);
+		pw.set_scalar_target(&b_biguint, b_value);
+		assert_eq!(pw.get_scalar_target(&a_biguint), a_value);
+
+		let proof = circuit.prove(pw)?;
+		circuit.verify(proof)
 	}
-}
 
+	#[test]
+	fn test_scalar_is_equal_and_select() -> Result<()> {
+		const D: usize = 2;
+		type C = PoseidonGoldilocksConfig;
+		type F = <C as GenericConfig<D>>::F;
+
+		let mut rng = thread_rng();
+
+		let config = CircuitConfig::standard_recursion_config();
+		let mut builder = CircuitBuilder::<F, D>::new(config);
+
+		let a_value = Scalar::sample(&mut rng);
+		let a = builder.add_virtual_scalar_target();
+		let a_biguint = builder.nonnative_to_canonical_biguint(&a);
+		let a_const = builder.scalar_constant(a_value);
+		// a constant with fewer limbs than a virtual target
+		let one = builder.scalar_constant(Scalar::ONE);
+
+		let a_is_a = builder.scalar_is_equal(&a, &a_const);
+		let a_is_one = builder.scalar_is_equal(&a, &one);
+		builder.assert_one(a_is_a.target);
+		builder.assert_zero(a_is_one.target);
+
+		let selected_a = builder.scalar_select(a_is_a, &a, &one);
+		let selected_one = builder.scalar_select(a_is_one, &a, &one);
+		builder.connect_nonnative(&selected_a, &a_const);
+		builder.connect_nonnative(&selected_one, &one);
+
+		let circuit = builder.build::<C>();
+
+		let mut pw = PartialWitness::new();
+		pw.set_scalar_target(&a_biguint, a_value);
+
+		let proof = circuit.prove(pw)?;
+		circuit.verify(proof)
+	}
+
+	#[test]
+	fn test_scalar_from_bits() -> Result<()> {
+		const D: usize = 2;
+		type C = PoseidonGoldilocksConfig;
+		type F = <C as GenericConfig<D>>::F;
+
+		let mut rng = thread_rng();
+
+		let config = CircuitConfig::standard_recursion_config();
+		let mut builder = CircuitBuilder::<F, D>::new(config);
+
+		let mut pw = PartialWitness::new();
+		for num_bits in [64, 318, 320, 512] {
+			let bits_value = (0..num_bits).map(|_| rng.gen::<bool>()).collect::<Vec<_>>();
+			let x_value = Scalar::from_noncanonical_biguint(
+				bits_value.iter().rev().fold(BigUint::default(), |acc, &bit| (acc << 1u32) + (bit as u32))
+			);
+
+			let bits = (0..num_bits).map(|_| builder.add_virtual_bool_target_safe()).collect::<Vec<_>>();
+			for (&bit, &bit_value) in bits.iter().zip(bits_value.iter()) {
+				pw.set_bool_target(bit, bit_value);
+			}
+
+			let x = builder.scalar_from_bits(&bits);
+			let x_expected = builder.scalar_constant(x_value);
+			builder.connect_nonnative(&x, &x_expected);
+		}
+
+		let circuit = builder.build::<C>();
+		let proof = circuit.prove(pw)?;
+		circuit.verify(proof)
+	}
+
+	#[test]
+	fn test_hash_to_scalar() -> Result<()> {
+		const D: usize = 2;
+		type C = PoseidonGoldilocksConfig;
+		type F = <C as GenericConfig<D>>::F;
+
+		let mut rng = thread_rng();
+
+		let config = CircuitConfig::standard_recursion_config();
+		let mut builder = CircuitBuilder::<F, D>::new(config);
+
+		// include an empty input and one that spans more than one Poseidon absorption
+		let mut pw = PartialWitness::new();
+		for (domain, num_inputs) in [(&b""[..], 0), (&b"test"[..], 3), (&b"a longer domain separation tag"[..], 11)] {
+			let inputs_value = (0..num_inputs).map(|_| F::sample(&mut rng)).collect::<Vec<_>>();
+			let inputs = builder.add_virtual_targets(num_inputs);
+			for (&t, &v) in inputs.iter().zip(inputs_value.iter()) {
+				pw.set_target(t, v);
+			}
+
+			let s = builder.hash_to_scalar(domain, &inputs);
+			let s_expected = builder.constant_nonnative(hash_to_scalar(domain, &inputs_value));
+			builder.connect_nonnative(&s, &s_expected);
+		}
+
+		let circuit = builder.build::<C>();
+		let proof = circuit.prove(pw)?;
+		circuit.verify(proof)
+	}
+}
@@ -0,0 +1,333 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::Field;
+use plonky2::iop::witness::Witness;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2_ecdsa::gadgets::biguint::BigUintTarget;
+use plonky2_ecdsa::gadgets::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+
+use crate::curve::scalar_field::Scalar;
+use crate::curve::sigma::{relation_shape, LinearProof, OrProof};
+use crate::curve::GFp;
+use crate::gadgets::curve::{CircuitBuilderEcGFp5, CurveTarget};
+use crate::gadgets::scalar_field::{CircuitBuilderScalar, PartialWitnessScalar};
+
+/// In-circuit counterpart of `sigma::LinearEquation`.
+#[derive(Clone, Debug)]
+pub struct LinearEquationTarget {
+    pub terms: Vec<(usize, CurveTarget)>,
+    pub image: CurveTarget,
+}
+
+/// In-circuit counterpart of `sigma::LinearRelation`.
+#[derive(Clone, Debug)]
+pub struct LinearRelationTarget {
+    pub num_secrets: usize,
+    pub equations: Vec<LinearEquationTarget>,
+}
+
+impl LinearRelationTarget {
+    pub fn schnorr(g: CurveTarget, y: CurveTarget) -> Self {
+        Self {
+            num_secrets: 1,
+            equations: vec![LinearEquationTarget { terms: vec![(0, g)], image: y }],
+        }
+    }
+
+    pub fn dleq(g: CurveTarget, y: CurveTarget, h: CurveTarget, z: CurveTarget) -> Self {
+        Self {
+            num_secrets: 1,
+            equations: vec![
+                LinearEquationTarget { terms: vec![(0, g)], image: y },
+                LinearEquationTarget { terms: vec![(0, h)], image: z },
+            ],
+        }
+    }
+
+    pub fn pedersen(g: CurveTarget, h: CurveTarget, y: CurveTarget) -> Self {
+        Self {
+            num_secrets: 2,
+            equations: vec![LinearEquationTarget { terms: vec![(0, g), (1, h)], image: y }],
+        }
+    }
+}
+
+/// In-circuit counterpart of `sigma::LinearProof`. Created with `add_virtual_linear_proof_target`.
+#[derive(Clone, Debug)]
+pub struct LinearProofTarget {
+    pub c: NonNativeTarget<Scalar>,
+    pub responses: Vec<NonNativeTarget<Scalar>>,
+    // the limbs of c and of every response, for the witness
+    limbs: Vec<BigUintTarget>,
+}
+
+/// In-circuit counterpart of `sigma::OrProof`. Created with `add_virtual_or_proof_target`.
+#[derive(Clone, Debug)]
+pub struct OrProofTarget {
+    pub challenges: Vec<NonNativeTarget<Scalar>>,
+    pub responses: Vec<Vec<NonNativeTarget<Scalar>>>,
+    // the limbs of every challenge, then of every response, for the witness
+    limbs: Vec<BigUintTarget>,
+}
+
+pub trait CircuitBuilderSigma {
+    fn add_virtual_linear_proof_target(&mut self, num_secrets: usize) -> LinearProofTarget;
+    /// `num_secrets[i]` is the number of secrets of the i-th relation
+    fn add_virtual_or_proof_target(&mut self, num_secrets: &[usize]) -> OrProofTarget;
+
+    /// Verifies a proof of knowledge of the secrets of `relation`, like `LinearRelation::verify`.
+    fn verify_linear_proof(&mut self, relation: &LinearRelationTarget, domain: &[u8], proof: &LinearProofTarget);
+    /// Verifies a proof of knowledge of the secrets of one of `relations`, like `LinearRelation::verify_or`.
+    fn verify_or_proof(&mut self, relations: &[LinearRelationTarget], domain: &[u8], proof: &OrProofTarget);
+}
+
+macro_rules! impl_circuit_builder_for_extension_degree {
+    ($degree:literal) => {
+        impl CircuitBuilderSigma for CircuitBuilder<GFp, $degree> {
+            fn add_virtual_linear_proof_target(&mut self, num_secrets: usize) -> LinearProofTarget {
+                let c = self.add_virtual_scalar_target();
+                let responses = (0..num_secrets).map(|_| self.add_virtual_scalar_target()).collect::<Vec<_>>();
+                let limbs = [&c].into_iter().chain(responses.iter()).map(|x| self.nonnative_to_canonical_biguint(x)).collect();
+
+                LinearProofTarget { c, responses, limbs }
+            }
+
+            fn add_virtual_or_proof_target(&mut self, num_secrets: &[usize]) -> OrProofTarget {
+                let challenges = num_secrets.iter().map(|_| self.add_virtual_scalar_target()).collect::<Vec<_>>();
+                let responses = num_secrets
+                    .iter()
+                    .map(|&n| (0..n).map(|_| self.add_virtual_scalar_target()).collect::<Vec<_>>())
+                    .collect::<Vec<_>>();
+                let limbs = challenges
+                    .iter()
+                    .chain(responses.iter().flatten())
+                    .map(|x| self.nonnative_to_canonical_biguint(x))
+                    .collect();
+
+                OrProofTarget { challenges, responses, limbs }
+            }
+
+            fn verify_linear_proof(&mut self, relation: &LinearRelationTarget, domain: &[u8], proof: &LinearProofTarget) {
+                assert_eq!(proof.responses.len(), relation.num_secrets);
+
+                let commitments = recompute_commitments(self, relation, &proof.c, &proof.responses);
+                let c = challenge(self, domain, &[relation], &[commitments]);
+                self.connect_nonnative(&proof.c, &c);
+            }
+
+            fn verify_or_proof(&mut self, relations: &[LinearRelationTarget], domain: &[u8], proof: &OrProofTarget) {
+                assert_eq!(proof.challenges.len(), relations.len());
+                assert_eq!(proof.responses.len(), relations.len());
+
+                let commitments = relations
+                    .iter()
+                    .zip(proof.challenges.iter().zip(proof.responses.iter()))
+                    .map(|(relation, (c, s))| {
+                        assert_eq!(s.len(), relation.num_secrets);
+                        recompute_commitments(self, relation, c, s)
+                    })
+                    .collect::<Vec<_>>();
+
+                let relation_refs = relations.iter().collect::<Vec<_>>();
+                let c = challenge(self, domain, &relation_refs, &commitments);
+
+                let mut challenges_sum = proof.challenges[0].clone();
+                for c_i in proof.challenges[1..].iter() {
+                    challenges_sum = self.scalar_add(&challenges_sum, c_i);
+                }
+                self.connect_nonnative(&challenges_sum, &c);
+            }
+        }
+    };
+}
+
+impl_circuit_builder_for_extension_degree!(1);
+impl_circuit_builder_for_extension_degree!(2);
+impl_circuit_builder_for_extension_degree!(4);
+impl_circuit_builder_for_extension_degree!(5);
+
+// Returns sum(s_i * P) - c * Y for every equation, pairing up the multiplications with `curve_muladd_2`.
+fn recompute_commitments<const D: usize>(
+    builder: &mut CircuitBuilder<GFp, D>,
+    relation: &LinearRelationTarget,
+    c: &NonNativeTarget<Scalar>,
+    responses: &[NonNativeTarget<Scalar>],
+) -> Vec<CurveTarget>
+where
+    GFp: Extendable<D>,
+    CircuitBuilder<GFp, D>: CircuitBuilderEcGFp5,
+{
+    relation
+        .equations
+        .iter()
+        .map(|eq| {
+            let mut pairs = eq.terms.iter().map(|&(i, base)| (base, &responses[i])).collect::<Vec<_>>();
+            let neg_image = builder.curve_neg(eq.image);
+            pairs.push((neg_image, c));
+
+            let terms = pairs
+                .chunks(2)
+                .map(|pair| match pair {
+                    [(a, s_a), (b, s_b)] => builder.curve_muladd_2(*a, *b, s_a, s_b),
+                    [(a, s_a)] => builder.curve_scalar_mul(*a, s_a),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>();
+            terms.into_iter().reduce(|acc, t| builder.curve_add(acc, t)).unwrap()
+        })
+        .collect()
+}
+
+// Same transcript as `sigma::challenge`.
+fn challenge<const D: usize>(
+    builder: &mut CircuitBuilder<GFp, D>,
+    domain: &[u8],
+    relations: &[&LinearRelationTarget],
+    commitments: &[Vec<CurveTarget>],
+) -> NonNativeTarget<Scalar>
+where
+    GFp: Extendable<D>,
+    CircuitBuilder<GFp, D>: CircuitBuilderEcGFp5,
+{
+    let mut inputs = vec![builder.constant(GFp::from_canonical_usize(relations.len()))];
+    for relation in relations {
+        let term_indices = relation
+            .equations
+            .iter()
+            .map(|eq| eq.terms.iter().map(|&(i, _)| i).collect())
+            .collect::<Vec<_>>();
+        for x in relation_shape(relation.num_secrets, &term_indices) {
+            inputs.push(builder.constant(x));
+        }
+
+        for eq in relation.equations.iter() {
+            for &(_, base) in eq.terms.iter() {
+                inputs.extend(builder.curve_encode_to_quintic_ext(base).0);
+            }
+            inputs.extend(builder.curve_encode_to_quintic_ext(eq.image).0);
+        }
+    }
+    for &t in commitments.iter().flatten() {
+        inputs.extend(builder.curve_encode_to_quintic_ext(t).0);
+    }
+
+    builder.hash_to_scalar(domain, &inputs)
+}
+
+pub trait PartialWitnessSigma: Witness<GFp> {
+    fn set_linear_proof_target(&mut self, target: &LinearProofTarget, value: &LinearProof);
+    fn set_or_proof_target(&mut self, target: &OrProofTarget, value: &OrProof);
+}
+
+impl<W: Witness<GFp>> PartialWitnessSigma for W {
+    fn set_linear_proof_target(&mut self, target: &LinearProofTarget, value: &LinearProof) {
+        assert_eq!(target.responses.len(), value.responses.len());
+
+        let values = [value.c].into_iter().chain(value.responses.iter().copied());
+        for (limbs, x) in target.limbs.iter().zip(values) {
+            self.set_scalar_target(limbs, x);
+        }
+    }
+
+    fn set_or_proof_target(&mut self, target: &OrProofTarget, value: &OrProof) {
+        assert_eq!(target.challenges.len(), value.challenges.len());
+        assert!(target.responses.iter().zip(value.responses.iter()).all(|(t, s)| t.len() == s.len()));
+
+        let values = value.challenges.iter().chain(value.responses.iter().flatten()).copied();
+        for (limbs, x) in target.limbs.iter().zip(values) {
+            self.set_scalar_target(limbs, x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::types::Sample;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::curve::curve::Point;
+    use crate::curve::sigma::LinearRelation;
+    use crate::gadgets::curve::PartialWitnessCurve;
+
+    #[test]
+    fn test_verify_dleq_proof() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let x = Scalar::sample(&mut rng);
+        let points = {
+            let h = Point::sample(&mut rng);
+            [Point::GENERATOR, Point::GENERATOR * x, h, h * x]
+        };
+        let proof_value = LinearRelation::dleq(points[0], points[1], points[2], points[3]).prove(b"dleq", &[x], &mut rng);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let point_targets = points.map(|_| builder.add_virtual_curve_target());
+        for &p in point_targets.iter() {
+            builder.register_curve_public_input(p);
+        }
+        let relation = LinearRelationTarget::dleq(point_targets[0], point_targets[1], point_targets[2], point_targets[3]);
+        let proof = builder.add_virtual_linear_proof_target(1);
+        builder.verify_linear_proof(&relation, b"dleq", &proof);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&t, p) in point_targets.iter().zip(points) {
+            pw.set_curve_target(t, p.to_weierstrass());
+        }
+        pw.set_linear_proof_target(&proof, &proof_value);
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+
+    #[test]
+    fn test_verify_or_proof() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        // knowledge of the secret key of one of two public keys
+        let x = Scalar::sample(&mut rng);
+        let pks = [Point::sample(&mut rng), Point::GENERATOR * x];
+        let relations = pks.map(|pk| LinearRelation::schnorr(Point::GENERATOR, pk));
+        let proof_value = LinearRelation::prove_or(&relations, b"or", 1, &[x], &mut rng);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let g = builder.curve_constant(Point::GENERATOR.to_weierstrass());
+        let pk_targets = pks.map(|_| builder.add_virtual_curve_target());
+        for &pk in pk_targets.iter() {
+            builder.register_curve_public_input(pk);
+        }
+        let relations = pk_targets.map(|pk| LinearRelationTarget::schnorr(g, pk));
+        let proof = builder.add_virtual_or_proof_target(&[1, 1]);
+        builder.verify_or_proof(&relations, b"or", &proof);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&t, pk) in pk_targets.iter().zip(pks) {
+            pw.set_curve_target(t, pk.to_weierstrass());
+        }
+        pw.set_or_proof_target(&proof, &proof_value);
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+}
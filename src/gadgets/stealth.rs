@@ -0,0 +1,123 @@
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2_ecdsa::gadgets::nonnative::NonNativeTarget;
+
+use crate::curve::curve::Point;
+use crate::curve::scalar_field::Scalar;
+use crate::curve::stealth::STEALTH_DOMAIN;
+use crate::curve::GFp;
+use crate::gadgets::curve::{CircuitBuilderEcGFp5, CurveTarget};
+use crate::gadgets::scalar_field::CircuitBuilderScalar;
+
+pub trait CircuitBuilderStealth {
+    /// Recomputes the one-time key sent with `ephemeral_pk` to the recipient with keys `view_sk` and `spend_pk`,
+    /// like `StealthViewKey::scan`.
+    /// WARNING: this alone proves nothing about the recipient. For any one-time key P, a prover can pick any
+    /// `view_sk` and set `spend_pk = P - tweak * G`. To keep the recipient private while proving that it is a
+    /// legitimate one, the caller must bind `view_sk` and `spend_pk` to something public, e.g. prove that
+    /// (view_sk * G, spend_pk) is in a public set of addresses (a Merkle root), or make the address a public input.
+    fn stealth_one_time_public_key(
+        &mut self,
+        view_sk: &NonNativeTarget<Scalar>,
+        spend_pk: CurveTarget,
+        ephemeral_pk: CurveTarget,
+    ) -> CurveTarget;
+
+    /// Asserts that `one_time_sk` is the secret key of `one_time_pk`, i.e. proves ownership of a stealth payment.
+    fn stealth_verify_one_time_secret_key(&mut self, one_time_sk: &NonNativeTarget<Scalar>, one_time_pk: CurveTarget);
+}
+
+macro_rules! impl_circuit_builder_for_extension_degree {
+    ($degree:literal) => {
+        impl CircuitBuilderStealth for CircuitBuilder<GFp, $degree> {
+            fn stealth_one_time_public_key(
+                &mut self,
+                view_sk: &NonNativeTarget<Scalar>,
+                spend_pk: CurveTarget,
+                ephemeral_pk: CurveTarget,
+            ) -> CurveTarget {
+                let shared = self.curve_scalar_mul(ephemeral_pk, view_sk);
+                let shared_encoded = self.curve_encode_to_quintic_ext(shared);
+                let tweak = self.hash_to_scalar(STEALTH_DOMAIN, &shared_encoded.0);
+                let tweak_times_g = self.curve_scalar_mul_const(Point::GENERATOR, &tweak);
+                self.curve_add(spend_pk, tweak_times_g)
+            }
+
+            fn stealth_verify_one_time_secret_key(&mut self, one_time_sk: &NonNativeTarget<Scalar>, one_time_pk: CurveTarget) {
+                let expected = self.curve_scalar_mul_const(Point::GENERATOR, one_time_sk);
+                let is_equal = self.curve_eq(expected, one_time_pk);
+                self.assert_one(is_equal.target);
+            }
+        }
+    };
+}
+
+impl_circuit_builder_for_extension_degree!(1);
+impl_circuit_builder_for_extension_degree!(2);
+impl_circuit_builder_for_extension_degree!(4);
+impl_circuit_builder_for_extension_degree!(5);
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::types::Sample;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2_ecdsa::gadgets::nonnative::CircuitBuilderNonNative;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::curve::stealth::StealthSecretKey;
+    use crate::gadgets::curve::PartialWitnessCurve;
+    use crate::gadgets::scalar_field::PartialWitnessScalar;
+
+    #[test]
+    fn test_stealth_ownership() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+
+        let recipient = StealthSecretKey::sample(&mut rng);
+        let (ephemeral_pk_value, one_time_pk_value) = recipient.address().derive_one_time_key(Scalar::sample(&mut rng));
+        let one_time_sk_value = recipient.one_time_secret_key(ephemeral_pk_value, one_time_pk_value).unwrap();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // the payment and the recipient address are public, the secret keys are not. To hide the address too,
+        // prove instead that it belongs to a public set of addresses
+        let ephemeral_pk = builder.add_virtual_curve_target();
+        let one_time_pk = builder.add_virtual_curve_target();
+        builder.register_curve_public_input(ephemeral_pk);
+        builder.register_curve_public_input(one_time_pk);
+
+        let view_sk = builder.add_virtual_scalar_target();
+        let spend_pk = builder.add_virtual_curve_target();
+        let one_time_sk = builder.add_virtual_scalar_target();
+        let view_pk = builder.curve_scalar_mul_const(Point::GENERATOR, &view_sk);
+        builder.register_curve_public_input(view_pk);
+        builder.register_curve_public_input(spend_pk);
+
+        let derived = builder.stealth_one_time_public_key(&view_sk, spend_pk, ephemeral_pk);
+        let is_equal = builder.curve_eq(derived, one_time_pk);
+        builder.assert_one(is_equal.target);
+        builder.stealth_verify_one_time_secret_key(&one_time_sk, one_time_pk);
+
+        let view_sk = builder.nonnative_to_canonical_biguint(&view_sk);
+        let one_time_sk = builder.nonnative_to_canonical_biguint(&one_time_sk);
+
+        let circuit = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_curve_target(ephemeral_pk, ephemeral_pk_value.to_weierstrass());
+        pw.set_curve_target(one_time_pk, one_time_pk_value.to_weierstrass());
+        pw.set_curve_target(spend_pk, recipient.address().spend_pk.to_weierstrass());
+        pw.set_scalar_target(&view_sk, recipient.view_sk);
+        pw.set_scalar_target(&one_time_sk, one_time_sk_value);
+
+        let proof = circuit.prove(pw)?;
+        circuit.verify(proof)
+    }
+}